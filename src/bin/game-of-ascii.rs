@@ -1,31 +1,36 @@
 use std::time::Duration;
 
-use game_of_life::core::{Cell, Config, State};
+use game_of_life::cli::Args;
+use game_of_life::core::{CycleDetector, Simulation, build_simulation};
 use game_of_life::render::ascii;
 
-const COLS: u32 = 80;
-const ROWS: u32 = 25;
-
 fn main() {
-    let config = Config {
-        cols: COLS,
-        rows: ROWS,
-    };
-
-    let mut state = State::new(&config);
+    let args = Args::default().from_env();
+    let pattern = args.load_pattern().unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+    let config = args.config(pattern.as_ref());
 
-    state.set_cell(40, 39, Cell::Alive);
-    state.set_cell(40, 40, Cell::Alive);
-    state.set_cell(40, 41, Cell::Alive);
-    state.set_cell(39, 40, Cell::Alive);
-    state.set_cell(41, 41, Cell::Alive);
+    let mut state = build_simulation(&config);
+    args.populate(&mut state, pattern.as_ref());
 
-    let mut frame = ascii::Frame::new(COLS, ROWS);
+    let mut frame = ascii::Frame::new(config.cols, config.rows);
+    let mut detector = CycleDetector::default();
 
     loop {
         ascii::render_ascii(&mut frame, &state);
+        frame.set_cycle(detector.observe(&state));
         ascii::draw_ascii(&frame);
-        state.next();
-        std::thread::sleep(Duration::from_millis(50));
+        if args
+            .generations
+            .is_some_and(|limit| state.generation() >= limit)
+        {
+            break;
+        }
+        state.step();
+        if let Some(delay) = args.step_delay_ms() {
+            std::thread::sleep(Duration::from_millis(delay));
+        }
     }
 }
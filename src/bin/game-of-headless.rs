@@ -0,0 +1,88 @@
+//! Runs a pattern or a soup without any display, as fast as possible, until
+//! the generation limit or until the board settles into a cycle, then prints
+//! a JSON summary on a single line. Without `--generations`, runs are capped
+//! and exit with status 3 when the cap is hit before any cycle is found.
+
+use std::{process, time::Instant};
+
+use game_of_life::{
+    cli::{Args, DEFAULT_HEADLESS_GENERATIONS},
+    core::{CycleDetector, Simulation, build_simulation, census::Census},
+};
+
+fn main() {
+    let args = Args::default().from_env();
+    let pattern = args.load_pattern().unwrap_or_else(|e| {
+        eprintln!("{e}");
+        process::exit(1);
+    });
+    let config = args.config(pattern.as_ref());
+
+    let mut state = build_simulation(&config);
+    let seed = args.populate(&mut state, pattern.as_ref());
+
+    let start = Instant::now();
+    let mut detector = CycleDetector::default();
+    let mut cycle = detector.observe(&state);
+    let limit = args.generations.unwrap_or(DEFAULT_HEADLESS_GENERATIONS);
+    while cycle.is_none() && state.generation() < limit {
+        state.step();
+        cycle = detector.observe(&state);
+    }
+    let elapsed = start.elapsed();
+    let capped = cycle.is_none() && args.generations.is_none();
+
+    let generations = state.generation();
+    let bounding_box = match state.bounding_box() {
+        Some(b) => format!(
+            r#"{{"x_min":{},"x_max":{},"y_min":{},"y_max":{}}}"#,
+            b.x_min, b.x_max, b.y_min, b.y_max
+        ),
+        None => "null".to_string(),
+    };
+    let cycle = match cycle {
+        Some(cycle) => format!(
+            r#"{{"start":{},"period":{},"dx":{},"dy":{},"speed":{}}}"#,
+            cycle.start,
+            cycle.period,
+            cycle.dx,
+            cycle.dy,
+            cycle
+                .speed()
+                .map_or("null".to_string(), |speed| format!(r#""{speed}""#)),
+        ),
+        None => "null".to_string(),
+    };
+    // objects separated by a single dead cell still interact
+    let census = if args.census {
+        let objects: Vec<_> = Census::new()
+            .count(&state, 1)
+            .into_iter()
+            .map(|(name, count)| format!(r#""{name}":{count}"#))
+            .collect();
+        format!(r#","census":{{{}}}"#, objects.join(","))
+    } else {
+        String::new()
+    };
+    let seed = seed.map_or("null".to_string(), |seed| seed.to_string());
+    let generations_per_second = generations as f64 / elapsed.as_secs_f64().max(f64::EPSILON);
+
+    println!(
+        r#"{{"rule":"{}","cols":{},"rows":{},"seed":{},"generations":{},"cycle":{},"population":{},"bounding_box":{},"elapsed_ms":{:.3},"generations_per_second":{:.1}{}}}"#,
+        config.rule,
+        config.cols,
+        config.rows,
+        seed,
+        generations,
+        cycle,
+        state.population(),
+        bounding_box,
+        elapsed.as_secs_f64() * 1000.0,
+        generations_per_second,
+        census,
+    );
+    if capped {
+        eprintln!("no cycle found within {limit} generations, use --generations to run longer");
+        process::exit(3);
+    }
+}
@@ -1,30 +1,42 @@
+use std::time::Duration;
+
 use game_of_life::{
-    core::{Cell, Config, State},
+    cli::Args,
+    core::{Simulation, build_simulation},
     render::kitty,
 };
 
-const COLS: u32 = 80;
-const ROWS: u32 = 25;
-
 fn main() -> Result<(), std::io::Error> {
-    let config = Config {
-        cols: COLS,
-        rows: ROWS,
-    };
-
-    let mut state = State::new(&config);
+    // the terminal redraw is the bottleneck, run at full speed by default
+    let args = Args {
+        speed: 0,
+        ..Args::default()
+    }
+    .from_env();
+    let pattern = args.load_pattern().unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+    let config = args.config(pattern.as_ref());
 
-    state.set_cell(40, 39, Cell::Alive);
-    state.set_cell(40, 40, Cell::Alive);
-    state.set_cell(40, 41, Cell::Alive);
-    state.set_cell(39, 40, Cell::Alive);
-    state.set_cell(41, 41, Cell::Alive);
+    let mut state = build_simulation(&config);
+    args.populate(&mut state, pattern.as_ref());
 
-    let mut frame = kitty::Frame::new(COLS, ROWS, 10);
+    let scale = args.scale.min(u8::MAX as u32) as u8;
+    let mut frame = kitty::Frame::new(config.cols, config.rows, scale);
 
     loop {
         kitty::render_kitty(&mut frame, &state);
         kitty::draw_kitty(&frame)?;
-        state.next();
+        if args
+            .generations
+            .is_some_and(|limit| state.generation() >= limit)
+        {
+            return Ok(());
+        }
+        state.step();
+        if let Some(delay) = args.step_delay_ms() {
+            std::thread::sleep(Duration::from_millis(delay));
+        }
     }
 }
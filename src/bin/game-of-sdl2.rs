@@ -1,5 +1,6 @@
 use game_of_life::{
-    core::{Cell, Config, State},
+    cli::Args,
+    core::build_simulation,
     render::sdl::{
         event_handler::EventHandler, game::Game, layout::Layout, renderer::Renderer, timer::Timer,
     },
@@ -7,21 +8,21 @@ use game_of_life::{
 
 // TODO write readme file explaining dependencies
 
-const COLS: u32 = 80;
-const ROWS: u32 = 80;
-const SCALE: u32 = 10;
-
 const FPS: u64 = 30;
 
 const FONT_SIZE: u16 = 12;
 
 fn main() -> Result<(), String> {
-    let config = Config {
-        cols: COLS,
-        rows: ROWS,
-    };
+    let args = Args {
+        rows: 80,
+        speed: 30,
+        ..Args::default()
+    }
+    .from_env();
+    let pattern = args.load_pattern().map_err(|e| e.to_string())?;
+    let config = args.config(pattern.as_ref());
 
-    let layout = Layout::new(&config, SCALE);
+    let layout = Layout::new(&config, args.scale);
 
     let sdl_ctx = sdl2::init()?;
     let ttf_ctx = sdl2::ttf::init()?;
@@ -52,15 +53,14 @@ fn main() -> Result<(), String> {
     let renderer = Renderer::new(layout, canvas, font);
     let timer = Timer::new(timer_sys, FPS);
 
-    let mut state = State::new(&config);
-
-    state.set_cell(40, 39, Cell::Alive);
-    state.set_cell(40, 40, Cell::Alive);
-    state.set_cell(40, 41, Cell::Alive);
-    state.set_cell(39, 40, Cell::Alive);
-    state.set_cell(41, 41, Cell::Alive);
+    let mut state = build_simulation(&config);
+    args.populate(&mut state, pattern.as_ref());
 
-    let mut game = Game::new(event_handler, renderer, timer, state);
+    let mut game = Game::new(event_handler, renderer, timer, state)
+        .with_clipboard(video_sys.clipboard())
+        .with_speed(args.speed)
+        .with_generation_limit(args.generations)
+        .with_history_limit(args.history << 20);
     game.run()?;
 
     Ok(())
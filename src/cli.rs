@@ -0,0 +1,368 @@
+//! Command line arguments shared by the binaries.
+//!
+//! Every binary starts from its own defaults, eg. a larger scale for graphical
+//! frontends, and overrides them with the arguments it was given:
+//!
+//! ```no_run
+//! use game_of_life::cli::Args;
+//!
+//! let args = Args { rows: 80, ..Args::default() }.from_env();
+//! ```
+
+use std::{
+    fmt,
+    path::PathBuf,
+    process,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::core::{
+    Backend, Cell, Config, Coords, Rule, Simulation, Soup, Symmetry, Topology,
+    format::{self, LoadError, Pattern},
+};
+
+// Density of random soups when only a seed is given.
+const DEFAULT_DENSITY: f64 = 0.5;
+
+/// Generations after which a headless run stops when no limit is given, the
+/// `--generations` help text quotes it.
+pub const DEFAULT_HEADLESS_GENERATIONS: u64 = 100_000;
+
+// Short name, long name, value name and description of each option.
+#[rustfmt::skip]
+const OPTIONS: &[(char, &str, &str, &str)] = &[
+    ('c', "cols",        "N",     "board width in cells"),
+    ('r', "rows",        "N",     "board height in cells"),
+    ('R', "rule",        "RULE",  "rule in B/S or S/B notation, eg. B3/S23"),
+    ('t', "topology",    "NAME",  "torus, plane, cylinder, klein or cross"),
+    ('b', "backend",     "NAME",  "board engine, cells or bits (Life-like rules only)"),
+    ('j', "threads",     "N",     "threads stepping the cells engine, 1 by default"),
+    ('p', "pattern",     "FILE",  "initial pattern, RLE, plaintext or Life 1.05/1.06"),
+    ('s', "seed",        "N",     "seed of a random initial soup"),
+    ('d', "density",     "D",     "density of the random soup, from 0 to 1"),
+    ('y', "symmetry",    "NAME",  "symmetry of the random soup, C1, C2, C4 or D8"),
+    ('S', "speed",       "N",     "generations per second, 0 runs at full speed"),
+    ('g', "generations", "N",     "stop after N generations, 100000 by default headless"),
+    ('z', "scale",       "N",     "size of a cell in pixels"),
+    ('m', "history",     "MB",    "memory used by the undo and rewind history"),
+    ('C', "census",      "",      "count the objects left at the end of the run"),
+    ('h', "help",        "",      "print this help"),
+];
+
+pub struct Args {
+    pub cols: u32,
+    pub rows: u32,
+    // The rule of the pattern file is used when no rule is given.
+    pub rule: Option<Rule>,
+    pub topology: Topology,
+    pub backend: Backend,
+    pub threads: usize,
+    pub pattern: Option<PathBuf>,
+    pub seed: Option<u64>,
+    pub density: Option<f64>,
+    pub symmetry: Symmetry,
+    pub speed: u32,
+    pub generations: Option<u64>,
+    pub scale: u32,
+    // Megabytes of board history kept for undo and rewind.
+    pub history: usize,
+    pub census: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ArgsError {
+    // Help was requested, the usage should be printed.
+    Help,
+    UnknownOption(String),
+    MissingValue(&'static str),
+    InvalidValue { option: &'static str, value: String },
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Args {
+            cols: 80,
+            rows: 25,
+            rule: None,
+            topology: Topology::default(),
+            backend: Backend::default(),
+            threads: 1,
+            pattern: None,
+            seed: None,
+            density: None,
+            symmetry: Symmetry::None,
+            speed: 20,
+            generations: None,
+            scale: 10,
+            history: 64,
+            census: false,
+        }
+    }
+}
+
+impl Args {
+    /// Parses the process arguments, printing the usage or the error and
+    /// exiting when they cannot be parsed.
+    pub fn from_env(self) -> Self {
+        let mut args = std::env::args();
+        let bin = args.next().unwrap_or_default();
+        match self.parse(args) {
+            Ok(args) => args,
+            Err(ArgsError::Help) => {
+                print!("{}", usage(&bin));
+                process::exit(0);
+            }
+            Err(e) => {
+                eprintln!("{bin}: {e}\nTry '{bin} --help' for more information.");
+                process::exit(2);
+            }
+        }
+    }
+
+    /// Overrides the arguments with the given ones, program name excluded.
+    /// Options accept their value either as the next argument or after `=`.
+    pub fn parse<I: IntoIterator<Item = String>>(mut self, args: I) -> Result<Self, ArgsError> {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            let option = OPTIONS.iter().find(|(short, long, ..)| {
+                name.strip_prefix("--") == Some(long) || name == format!("-{short}")
+            });
+            let Some(&(_, long, value_name, _)) = option else {
+                return Err(ArgsError::UnknownOption(name));
+            };
+            if value_name.is_empty() {
+                match long {
+                    "help" => return Err(ArgsError::Help),
+                    "census" => self.census = true,
+                    _ => unreachable!("flag without handler: {long}"),
+                }
+                continue;
+            }
+
+            let value = inline_value
+                .or_else(|| args.next())
+                .ok_or(ArgsError::MissingValue(long))?;
+            let invalid = || ArgsError::InvalidValue {
+                option: long,
+                value: value.clone(),
+            };
+            match long {
+                "cols" => self.cols = value.parse().ok().filter(|&n| n > 0).ok_or_else(invalid)?,
+                "rows" => self.rows = value.parse().ok().filter(|&n| n > 0).ok_or_else(invalid)?,
+                "rule" => self.rule = Some(value.parse().map_err(|_| invalid())?),
+                "topology" => self.topology = value.parse().map_err(|_| invalid())?,
+                "backend" => self.backend = value.parse().map_err(|_| invalid())?,
+                "threads" => {
+                    self.threads = value.parse().ok().filter(|&n| n > 0).ok_or_else(invalid)?
+                }
+                "pattern" => self.pattern = Some(PathBuf::from(&value)),
+                "seed" => self.seed = Some(value.parse().map_err(|_| invalid())?),
+                "density" => {
+                    let density = value.parse().ok().filter(|d| (0.0..=1.0).contains(d));
+                    self.density = Some(density.ok_or_else(invalid)?);
+                }
+                "symmetry" => self.symmetry = value.parse().map_err(|_| invalid())?,
+                "speed" => self.speed = value.parse().map_err(|_| invalid())?,
+                "generations" => self.generations = Some(value.parse().map_err(|_| invalid())?),
+                "scale" => {
+                    self.scale = value.parse().ok().filter(|&n| n > 0).ok_or_else(invalid)?
+                }
+                "history" => self.history = value.parse().map_err(|_| invalid())?,
+                _ => unreachable!("option without handler: {long}"),
+            }
+        }
+        Ok(self)
+    }
+
+    /// Reads the pattern file, if any.
+    pub fn load_pattern(&self) -> Result<Option<Pattern>, LoadError> {
+        self.pattern.as_ref().map(format::load).transpose()
+    }
+
+    /// Returns the board configuration. Without a rule argument, the rule of
+    /// the pattern is used.
+    pub fn config(&self, pattern: Option<&Pattern>) -> Config {
+        let rule = self
+            .rule
+            .or_else(|| pattern.and_then(|pattern| pattern.rule))
+            .unwrap_or_default();
+        Config {
+            cols: self.cols,
+            rows: self.rows,
+            rule,
+            topology: self.topology,
+            backend: self.backend,
+            threads: self.threads,
+            ..Config::default()
+        }
+    }
+
+    /// Fills the simulation with the pattern centred on the board, or with a
+    /// random soup when a seed, a density or a symmetry is given, or else with
+    /// the R-pentomino.
+    ///
+    /// Returns the seed of the soup, which can be reported to reproduce it.
+    pub fn populate<S: Simulation + ?Sized>(
+        &self,
+        sim: &mut S,
+        pattern: Option<&Pattern>,
+    ) -> Option<u64> {
+        let (cols, rows) = (self.cols as i32, self.rows as i32);
+        if let Some(pattern) = pattern {
+            let x = (cols - pattern.width as i32) / 2;
+            let y = (rows - pattern.height as i32) / 2;
+            pattern.place(sim, x, y);
+        } else if let Some(soup) = self.soup() {
+            for Coords { x, y } in soup.cells(self.cols, self.rows) {
+                sim.set_cell(x, y, Cell::Alive);
+            }
+            return Some(soup.seed);
+        } else {
+            for (dx, dy) in [(0, -1), (0, 0), (0, 1), (-1, 0), (1, 1)] {
+                sim.set_cell(cols / 2 + dx, rows / 2 + dy, Cell::Alive);
+            }
+        }
+        None
+    }
+
+    /// Returns the random soup described by the arguments, if any. A soup
+    /// given a density or a symmetry but no seed gets one from the clock.
+    pub fn soup(&self) -> Option<Soup> {
+        let seed = match self.seed {
+            Some(seed) => seed,
+            None if self.density.is_some() || self.symmetry != Symmetry::None => time_seed(),
+            None => return None,
+        };
+        Some(Soup {
+            density: self.density.unwrap_or(DEFAULT_DENSITY),
+            symmetry: self.symmetry,
+            ..Soup::new(seed)
+        })
+    }
+
+    /// Returns the delay between two generations, none at full speed.
+    pub fn step_delay_ms(&self) -> Option<u64> {
+        (self.speed > 0).then(|| 1000 / self.speed as u64)
+    }
+}
+
+fn time_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+}
+
+/// Returns the help text of the program.
+pub fn usage(bin: &str) -> String {
+    let mut usage = format!("Usage: {bin} [OPTIONS]\n\nOptions:\n");
+    for (short, long, value_name, description) in OPTIONS {
+        let option = format!("-{short}, --{long} {value_name}");
+        usage.push_str(&format!("  {option:<26}{description}\n"));
+    }
+    usage
+}
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgsError::Help => write!(f, "help requested"),
+            ArgsError::UnknownOption(option) => write!(f, "unknown option '{option}'"),
+            ArgsError::MissingValue(option) => write!(f, "missing value for --{option}"),
+            ArgsError::InvalidValue { option, value } => {
+                write!(f, "invalid value '{value}' for --{option}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArgsError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::State;
+
+    fn parse(args: &str) -> Result<Args, ArgsError> {
+        Args::default().parse(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn parses_short_and_long_options() {
+        let args =
+            parse("-c 40 --rows=30 -R B36/S23 --topology plane -s 7 -C -g 100 -m 8 -b bits -j 4")
+                .unwrap();
+        assert_eq!((args.cols, args.rows), (40, 30));
+        assert_eq!(args.rule, Some("B36/S23".parse().unwrap()));
+        assert_eq!(args.topology, Topology::Plane);
+        assert_eq!(args.seed, Some(7));
+        assert_eq!(args.generations, Some(100));
+        assert_eq!(args.history, 8);
+        let config = args.config(None);
+        assert_eq!((config.backend, config.threads), (Backend::Bits, 4));
+        assert!(args.census);
+        assert_eq!(args.speed, Args::default().speed);
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert_eq!(parse("-h").err(), Some(ArgsError::Help));
+        assert_eq!(
+            parse("--colour 3").err(),
+            Some(ArgsError::UnknownOption("--colour".to_string()))
+        );
+        assert_eq!(parse("-c").err(), Some(ArgsError::MissingValue("cols")));
+        assert_eq!(
+            parse("--density 2").err(),
+            Some(ArgsError::InvalidValue {
+                option: "density",
+                value: "2".to_string()
+            })
+        );
+        assert!(parse("--rows 0").is_err());
+        assert!(parse("--rule B9").is_err());
+    }
+
+    #[test]
+    fn seeds_soups_without_seed() {
+        let args = parse("-d 0.2").unwrap();
+        assert_eq!(args.seed, None);
+        assert!(args.soup().is_some());
+        assert!(parse("-c 10").unwrap().soup().is_none());
+    }
+
+    #[test]
+    fn lists_every_option_in_usage() {
+        let usage = usage("game");
+        assert!(OPTIONS.iter().all(|(_, long, ..)| usage.contains(long)));
+    }
+
+    #[test]
+    fn populates_reproducible_soups() {
+        let args = parse("-c 20 -r 20 -s 3 -d 0.4 -y c2").unwrap();
+        assert_eq!(args.symmetry, Symmetry::C2);
+        let config = args.config(None);
+        let (mut a, mut b) = (State::new(&config), State::new(&config));
+        assert_eq!(args.populate(&mut a, None), Some(3));
+        args.populate(&mut b, None);
+        assert!(a.population() > 0);
+        assert!(a.live_cells().eq(b.live_cells()));
+    }
+
+    #[test]
+    fn centres_pattern_and_uses_its_rule() {
+        let pattern = format::parse("x = 3, y = 1, rule = B36/S23\n3o!").unwrap();
+        let args = parse("-c 9 -r 9").unwrap();
+        let config = args.config(Some(&pattern));
+        assert_eq!(config.rule, "B36/S23".parse().unwrap());
+
+        let mut state = State::new(&config);
+        args.populate(&mut state, Some(&pattern));
+        assert_eq!(state.get_cell(3, 4), Cell::Alive);
+        assert_eq!(state.get_cell(5, 4), Cell::Alive);
+        assert_eq!(state.population(), 3);
+    }
+}
@@ -1,9 +1,35 @@
+mod bitboard;
+mod bounds;
 mod cell;
+pub mod census;
 mod config;
 mod coords;
+mod cycle;
+pub mod format;
+mod hashlife;
+pub mod library;
+pub mod rng;
+mod rule;
+mod simulation;
+mod soup;
+mod sparse;
 mod state;
+mod stats;
+#[cfg(test)]
+pub(crate) mod test_support;
+mod topology;
 
+pub use bitboard::BitState;
+pub use bounds::Bounds;
 pub use cell::Cell;
-pub use config::Config;
+pub use config::{Backend, Config, ParseBackendError};
 pub use coords::Coords;
+pub use cycle::{Cycle, CycleDetector};
+pub use hashlife::{HashLife, HashLifeError};
+pub use rule::{ParseRuleError, Rule};
+pub use simulation::{Simulation, build_simulation};
+pub use soup::{ParseSymmetryError, Soup, Symmetry};
+pub use sparse::SparseState;
 pub use state::{BoardIterator, State};
+pub use stats::{Sample, Stats};
+pub use topology::{ParseTopologyError, Topology};
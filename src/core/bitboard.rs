@@ -0,0 +1,351 @@
+use std::mem;
+
+use super::{
+    cell::Cell, config::Config, coords::Coords, rule::Rule, simulation::Simulation,
+    topology::Topology,
+};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Bit-packed Game of Life state
+///
+/// Packs 64 cells per `u64` word, so that the whole board fits in a fraction
+/// of the memory used by `State` and a single bitwise operation evaluates 64
+/// cells at once. Only Life-like rules with two states are supported.
+///
+/// Every row is padded with one ghost cell on each side, and the board with
+/// one ghost row on top and one at the bottom. Before each step the ghost
+/// cells are filled with copies of the cells the topology glues to the edges,
+/// so that the evolution never needs to care about board edges.
+///
+/// ```txt
+/// bit    0   1   2       cols  cols+1
+///      +---+---+---+ ... +---+---+
+///      | g | 0 | 1 | ... |c-1| g |
+///      +---+---+---+ ... +---+---+
+/// ```
+pub struct BitState {
+    generation: u64,
+    rule: Rule,
+    topology: Topology,
+
+    // Board
+    cols: u32,
+    rows: u32,
+    words: usize,
+    curr: Vec<u64>,
+    next: Vec<u64>,
+}
+
+impl BitState {
+    pub fn new(config: &Config) -> Self {
+        let words = (config.cols as usize + 2).div_ceil(WORD_BITS);
+        let board_capacity = words * (config.rows as usize + 2);
+
+        BitState {
+            generation: 0,
+            rule: config.rule,
+            topology: config.topology,
+            cols: config.cols,
+            rows: config.rows,
+            words,
+            curr: vec![0; board_capacity],
+            next: vec![0; board_capacity],
+        }
+    }
+
+    /// Returns word index and bit offset of padded coordinates (x,y), where
+    /// (0,0) is the top-left ghost cell.
+    fn locate(&self, x: usize, y: usize) -> (usize, usize) {
+        (y * self.words + x / WORD_BITS, x % WORD_BITS)
+    }
+
+    fn get_padded(&self, x: usize, y: usize) -> bool {
+        let (word, bit) = self.locate(x, y);
+        self.curr[word] >> bit & 1 == 1
+    }
+
+    fn set_padded(&mut self, x: usize, y: usize, alive: bool) {
+        let (word, bit) = self.locate(x, y);
+        if alive {
+            self.curr[word] |= 1 << bit;
+        } else {
+            self.curr[word] &= !(1 << bit);
+        }
+    }
+
+    /// Copies into each ghost cell the board cell the topology maps it onto.
+    ///
+    /// A corner ghost cell neighbors a single board cell, which a twisted
+    /// topology may map the ghost back onto: that cell does not count itself,
+    /// so the ghost is left dead.
+    fn fill_ghosts(&mut self) {
+        let (cols, rows) = (self.cols as i32, self.rows as i32);
+        let top_bottom = (-1..=cols).flat_map(|x| [(x, -1), (x, rows)]);
+        let left_right = (0..rows).flat_map(|y| [(-1, y), (cols, y)]);
+
+        for (x, y) in top_bottom.chain(left_right) {
+            let viewer = (x.clamp(0, cols - 1), y.clamp(0, rows - 1));
+            let is_corner = (x == -1 || x == cols) && (y == -1 || y == rows);
+            let alive = match self.topology.wrap(x, y, self.cols, self.rows) {
+                Some(source) if is_corner && source == viewer => false,
+                Some((sx, sy)) => self.get_padded(sx as usize + 1, sy as usize + 1),
+                None => false,
+            };
+            self.set_padded((x + 1) as usize, (y + 1) as usize, alive);
+        }
+    }
+
+    /// Returns the mask of the board cells in a word of a padded row, ghost
+    /// cells and trailing padding excluded.
+    fn board_mask(&self, word: usize) -> u64 {
+        let first = word * WORD_BITS;
+        (0..WORD_BITS)
+            .filter(|bit| (1..=self.cols as usize).contains(&(first + bit)))
+            .fold(0, |mask, bit| mask | 1 << bit)
+    }
+
+    /// Computes the next generation of a row of words, given the rows above and
+    /// below it.
+    ///
+    /// The 8 neighbors of every cell are the rows shifted by one bit east and
+    /// west, and they are summed up bitwise with full adders into the four bits
+    /// of the neighbor count.
+    fn next_row(&self, above: &[u64], row: &[u64], below: &[u64], masks: &[u64], out: &mut [u64]) {
+        let word_at = |words: &[u64], i: isize| -> u64 {
+            usize::try_from(i)
+                .ok()
+                .and_then(|i| words.get(i))
+                .copied()
+                .unwrap_or(0)
+        };
+        // bit i takes the bit i-1 of the row, that is the west neighbor
+        let west = |words: &[u64], w: usize| {
+            words[w] << 1 | word_at(words, w as isize - 1) >> (WORD_BITS - 1)
+        };
+        // bit i takes the bit i+1 of the row, that is the east neighbor
+        let east = |words: &[u64], w: usize| {
+            words[w] >> 1 | word_at(words, w as isize + 1) << (WORD_BITS - 1)
+        };
+
+        for w in 0..row.len() {
+            let neighbors = [
+                west(above, w),
+                above[w],
+                east(above, w),
+                west(row, w),
+                east(row, w),
+                west(below, w),
+                below[w],
+                east(below, w),
+            ];
+            let count = count_bits(neighbors);
+            let alive = row[w];
+
+            let (mut born, mut survive) = (0, 0);
+            for n in 0..=8 {
+                let is_birth = self.rule.is_birth(n);
+                let is_survival = self.rule.is_survival(n);
+                if is_birth || is_survival {
+                    let matches = count_equals(count, n);
+                    born |= if is_birth { matches } else { 0 };
+                    survive |= if is_survival { matches } else { 0 };
+                }
+            }
+
+            out[w] = ((alive & survive) | (!alive & born)) & masks[w];
+        }
+    }
+}
+
+/// Bit-sliced sum of eight bitmasks, returned as the four bits of the count,
+/// least significant first.
+fn count_bits(n: [u64; 8]) -> [u64; 4] {
+    let (s_a, c_a) = full_add(n[0], n[1], n[2]);
+    let (s_b, c_b) = full_add(n[3], n[4], n[5]);
+    let (s_c, c_c) = half_add(n[6], n[7]);
+    // ones, and the carries of weight two
+    let (b0, c_d) = full_add(s_a, s_b, s_c);
+    let (t, e_a) = full_add(c_a, c_b, c_c);
+    // twos, and the carries of weight four
+    let (b1, e_b) = half_add(t, c_d);
+    // fours and eights, the count never exceeds 8
+    let (b2, b3) = half_add(e_a, e_b);
+    [b0, b1, b2, b3]
+}
+
+/// Returns the mask of the cells whose bit-sliced count equals `n`.
+fn count_equals(count: [u64; 4], n: u8) -> u64 {
+    count.iter().enumerate().fold(!0, |mask, (i, &bits)| {
+        mask & if n >> i & 1 == 1 { bits } else { !bits }
+    })
+}
+
+fn full_add(a: u64, b: u64, c: u64) -> (u64, u64) {
+    let t = a ^ b;
+    (t ^ c, (a & b) | (t & c))
+}
+
+fn half_add(a: u64, b: u64) -> (u64, u64) {
+    (a ^ b, a & b)
+}
+
+impl Simulation for BitState {
+    fn step(&mut self) {
+        self.fill_ghosts();
+
+        let words = self.words;
+        let masks: Vec<u64> = (0..words).map(|w| self.board_mask(w)).collect();
+        let mut next = mem::take(&mut self.next);
+        next.fill(0);
+
+        for y in 1..=self.rows as usize {
+            let above = &self.curr[(y - 1) * words..y * words];
+            let row = &self.curr[y * words..(y + 1) * words];
+            let below = &self.curr[(y + 1) * words..(y + 2) * words];
+            self.next_row(
+                above,
+                row,
+                below,
+                &masks,
+                &mut next[y * words..(y + 1) * words],
+            );
+        }
+
+        self.next = next;
+        self.generation += 1;
+        mem::swap(&mut self.curr, &mut self.next);
+    }
+
+    fn get_cell(&self, x: i32, y: i32) -> Cell {
+        match self.topology.wrap(x, y, self.cols, self.rows) {
+            Some((x, y)) if self.get_padded(x as usize + 1, y as usize + 1) => Cell::Alive,
+            _ => Cell::Dead,
+        }
+    }
+
+    /// Dying cells are not supported and are set as dead.
+    fn set_cell(&mut self, x: i32, y: i32, value: Cell) {
+        if let Some((x, y)) = self.topology.wrap(x, y, self.cols, self.rows) {
+            self.set_padded(x as usize + 1, y as usize + 1, value == Cell::Alive);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.curr.fill(0);
+    }
+
+    fn live_cells(&self) -> Box<dyn Iterator<Item = (Coords, Cell)> + '_> {
+        let (cols, rows) = (self.cols as usize, self.rows as usize);
+        Box::new(
+            (1..=rows)
+                .flat_map(move |y| (1..=cols).map(move |x| (x, y)))
+                .filter(|&(x, y)| self.get_padded(x, y))
+                .map(|(x, y)| {
+                    let coords = Coords {
+                        x: x as i32 - 1,
+                        y: y as i32 - 1,
+                    };
+                    (coords, Cell::Alive)
+                }),
+        )
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+
+    fn rule(&self) -> &Rule {
+        &self.rule
+    }
+
+    fn wrap_coords(&self, coords: &Coords) -> Option<Coords> {
+        self.topology
+            .wrap(coords.x, coords.y, self.cols, self.rows)
+            .map(Coords::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::State;
+
+    /// Minimal linear congruential generator, enough to scatter test soups.
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next_bool(&mut self) -> bool {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            self.0 >> 62 == 0
+        }
+    }
+
+    fn assert_agree(config: &Config, seed: u64, generations: usize) {
+        let mut state = State::new(config);
+        let mut bits = BitState::new(config);
+        let mut lcg = Lcg(seed);
+        for y in 0..config.rows as i32 {
+            for x in 0..config.cols as i32 {
+                if lcg.next_bool() {
+                    state.set_cell(x, y, Cell::Alive);
+                    bits.set_cell(x, y, Cell::Alive);
+                }
+            }
+        }
+
+        for generation in 0..generations {
+            let expected: Vec<_> = state.live_cells().collect();
+            let actual: Vec<_> = bits.live_cells().collect();
+            assert_eq!(actual, expected, "generation {generation}");
+            state.step();
+            bits.step();
+        }
+    }
+
+    #[test]
+    fn agrees_with_state_on_random_soups() {
+        for (i, topology) in [
+            Topology::Torus,
+            Topology::Plane,
+            Topology::Cylinder,
+            Topology::KleinBottle,
+            Topology::CrossSurface,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            // 130 columns span three words, exercising carries between words
+            let config = Config {
+                cols: 130,
+                rows: 40,
+                topology,
+                ..Config::default()
+            };
+            assert_agree(&config, i as u64, 100);
+        }
+    }
+
+    #[test]
+    fn agrees_with_state_on_other_rules() {
+        for (i, rule) in ["B36/S23", "B2/S", "B3678/S34678", "B1/S012345678"]
+            .into_iter()
+            .enumerate()
+        {
+            let config = Config {
+                cols: 64,
+                rows: 33,
+                rule: rule.parse().unwrap(),
+                ..Config::default()
+            };
+            assert_agree(&config, 42 + i as u64, 50);
+        }
+    }
+}
@@ -0,0 +1,43 @@
+//! Axis-aligned bounding box of a set of cells, inclusive on every side.
+
+use super::coords::Coords;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Bounds {
+    pub x_min: i32,
+    pub x_max: i32,
+    pub y_min: i32,
+    pub y_max: i32,
+}
+
+impl Bounds {
+    /// Returns the smallest bounds containing all coordinates, or `None` when
+    /// there are no coordinates at all.
+    #[rustfmt::skip]
+    pub fn from_coords<I: IntoIterator<Item = Coords>>(coords: I) -> Option<Self> {
+        coords
+            .into_iter()
+            .fold(None, |bounds: Option<Bounds>, Coords { x, y }| match bounds {
+                None => Some(Bounds { x_min: x, x_max: x, y_min: y, y_max: y }),
+                Some(bounds) => Some(Bounds {
+                    x_min: x.min(bounds.x_min),
+                    x_max: x.max(bounds.x_max),
+                    y_min: y.min(bounds.y_min),
+                    y_max: y.max(bounds.y_max),
+                }),
+            })
+    }
+
+    pub fn contains(&self, coords: &Coords) -> bool {
+        (self.x_min..=self.x_max).contains(&coords.x)
+            && (self.y_min..=self.y_max).contains(&coords.y)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.x_min.abs_diff(self.x_max) + 1
+    }
+
+    pub fn height(&self) -> u32 {
+        self.y_min.abs_diff(self.y_max) + 1
+    }
+}
@@ -6,35 +6,105 @@
 //! otherwise it becomes dead.
 //! - A dead cell that has exactly 3 living neighbors becomes alive, otherwise
 //! it stays dead.
+//!
+//! Other Life-like automata only change those neighbor counts, which are
+//! described by a `Rule`.
+//!
+//! Generations rules add dying states: a living cell that does not survive
+//! decays through `Dying(1)`, `Dying(2)`, ... before becoming dead. Dying cells
+//! are not counted as living neighbors and cannot be born into.
+
+use super::rule::Rule;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Cell {
     Dead,
     Alive,
+    Dying(u8),
 }
 
 impl Cell {
     pub fn as_value(&self) -> u8 {
+        match self {
+            Cell::Alive => 1,
+            Cell::Dead | Cell::Dying(_) => 0,
+        }
+    }
+
+    /// Returns the state index in Generations numbering: 0 is dead, 1 is alive
+    /// and 2 onward are the dying states. Wider than the dying counter, so
+    /// that `Dying(255)` has a state too.
+    pub fn state(&self) -> u16 {
         match self {
             Cell::Dead => 0,
             Cell::Alive => 1,
+            Cell::Dying(n) => *n as u16 + 1,
+        }
+    }
+
+    /// Inverse of `state`, maps a state index back into a cell.
+    pub fn from_state(state: u8) -> Self {
+        match state {
+            0 => Cell::Dead,
+            1 => Cell::Alive,
+            n => Cell::Dying(n - 1),
         }
     }
 
-    pub fn next(&self, alive_neighbors: u8) -> Self {
-        match (self, alive_neighbors) {
-            (Cell::Alive, 2 | 3) => Cell::Alive,
-            (Cell::Alive, _) => Cell::Dead,
-            (Cell::Dead, 3) => Cell::Alive,
-            (Cell::Dead, _) => Cell::Dead,
+    pub fn next(&self, alive_neighbors: u8, rule: &Rule) -> Self {
+        match self {
+            Cell::Alive if rule.is_survival(alive_neighbors) => Cell::Alive,
+            Cell::Alive | Cell::Dying(_) => self.decay(rule),
+            Cell::Dead if rule.is_birth(alive_neighbors) => Cell::Alive,
+            Cell::Dead => Cell::Dead,
         }
     }
 
-    /// Flip cell state, `Dead -> Alive`, `Alive -> Dead`.
+    /// Advance a non-surviving cell to the following state: `Alive` starts
+    /// dying (or dies right away under Life-like rules), and dying cells get
+    /// one step closer to death.
+    fn decay(&self, rule: &Rule) -> Self {
+        let next_state = self.state() + 1;
+        if next_state < rule.states() as u16 {
+            Cell::from_state(next_state as u8)
+        } else {
+            Cell::Dead
+        }
+    }
+
+    /// Flip cell state, `Dead -> Alive`, `Alive -> Dead`. Dying cells are
+    /// revived.
     pub fn toggle(&mut self) {
         *self = match self {
             Cell::Alive => Cell::Dead,
-            Cell::Dead => Cell::Alive,
+            Cell::Dead | Cell::Dying(_) => Cell::Alive,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decays_through_dying_states() {
+        let rule: Rule = "B2/S/C4".parse().unwrap();
+
+        let cell = Cell::Alive.next(2, &rule);
+        assert_eq!(cell, Cell::Dying(1));
+        assert_eq!(cell.state(), 2);
+
+        // dying cells ignore their neighbors
+        let cell = cell.next(2, &rule);
+        assert_eq!(cell, Cell::Dying(2));
+        assert_eq!(cell.next(2, &rule), Cell::Dead);
+        assert_eq!(Cell::Dying(255).state(), 256);
+    }
+
+    #[test]
+    fn dies_right_away_under_life_like_rules() {
+        let rule = Rule::default();
+        assert_eq!(Cell::Alive.next(1, &rule), Cell::Dead);
+        assert_eq!(Cell::Dead.next(3, &rule), Cell::Alive);
+    }
+}
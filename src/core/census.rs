@@ -0,0 +1,255 @@
+//! Census of the objects on a board.
+//!
+//! Live cells are split into connected objects, and each object is brought
+//! into a canonical form, the same under any rotation or reflection, which is
+//! looked up among the still lifes, oscillators and spaceships of the library.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::{
+    coords::Coords,
+    cycle::{Cycle, CycleDetector},
+    library,
+    rule::Rule,
+    simulation::Simulation,
+    sparse::SparseState,
+};
+
+// Generations searched for the period of the library patterns, well over the
+// 15 of the pentadecathlon.
+const PERIOD_MAX: u64 = 30;
+
+// Rotation or reflection of a cell.
+type Image = fn(i32, i32) -> (i32, i32);
+
+/// Canonical form of an object: its cells relative to the top-left corner of
+/// their bounds, sorted, and the smallest of the eight symmetric images.
+pub type Shape = Vec<(i32, i32)>;
+
+/// Identifies objects from a table of common objects in every phase.
+pub struct Census {
+    known: HashMap<Shape, &'static str>,
+}
+
+impl Census {
+    /// Builds the table of known objects from the library patterns repeating
+    /// their first phase, running each of them through its period to record
+    /// every phase.
+    pub fn new() -> Self {
+        let mut known = HashMap::new();
+        for (name, _) in library::list() {
+            let pattern = library::get(name).expect("listed patterns exist");
+            let build = || {
+                let mut sim = SparseState::new(Rule::default());
+                pattern.place(&mut sim, 0, 0);
+                sim
+            };
+            // guns and methuselahs never come back to their first phase
+            let cycle = CycleDetector::default().run(&mut build(), PERIOD_MAX);
+            let Some(Cycle {
+                start: 0, period, ..
+            }) = cycle
+            else {
+                continue;
+            };
+            let mut sim = build();
+            for _ in 0..period {
+                let cells: Vec<_> = sim.live_cells().map(|(coords, _)| coords).collect();
+                known.insert(canonical(&cells), name);
+                sim.step();
+            }
+        }
+        Census { known }
+    }
+
+    /// Returns the name of a known object.
+    pub fn identify(&self, cells: &[Coords]) -> Option<&'static str> {
+        self.known.get(&canonical(cells)).copied()
+    }
+
+    /// Counts the objects of a simulation, splitting cells further apart than
+    /// `gap` dead cells. Known objects are counted by name, under Conway's
+    /// Life only, the others by their canonical rows.
+    pub fn count<S: Simulation + ?Sized>(&self, sim: &S, gap: u32) -> BTreeMap<String, usize> {
+        let is_life = *sim.rule() == Rule::default();
+        let mut census = BTreeMap::new();
+        for object in components(sim, gap) {
+            let name = match self.identify(&object) {
+                Some(name) if is_life => name.to_string(),
+                _ => shape_rows(&canonical(&object)),
+            };
+            *census.entry(name).or_insert(0) += 1;
+        }
+        census
+    }
+}
+
+impl Default for Census {
+    fn default() -> Self {
+        Census::new()
+    }
+}
+
+/// Splits the non-dead cells of a simulation into connected objects, where
+/// cells at most `gap` dead cells apart, in any direction, are connected.
+///
+/// Objects crossing the edges of a wrapping board are followed across them,
+/// so their cells may fall off the board, but keep their shape.
+pub fn components<S: Simulation + ?Sized>(sim: &S, gap: u32) -> Vec<Vec<Coords>> {
+    let reach = gap as i32 + 1;
+    let mut unvisited: HashSet<Coords> = sim.live_cells().map(|(coords, _)| coords).collect();
+    let mut objects = Vec::new();
+
+    while let Some(&start) = unvisited.iter().next() {
+        unvisited.remove(&start);
+        let mut object = vec![start];
+        let mut i = 0;
+        while i < object.len() {
+            let Coords { x, y } = object[i];
+            for dy in -reach..=reach {
+                for dx in -reach..=reach {
+                    let neighbor = Coords {
+                        x: x + dx,
+                        y: y + dy,
+                    };
+                    let on_board = sim.wrap_coords(&neighbor);
+                    if on_board.is_some_and(|coords| unvisited.remove(&coords)) {
+                        object.push(neighbor);
+                    }
+                }
+            }
+            i += 1;
+        }
+        objects.push(object);
+    }
+    objects
+}
+
+/// Returns the canonical form of an object.
+pub fn canonical(cells: &[Coords]) -> Shape {
+    #[rustfmt::skip]
+    let images: [Image; 8] = [
+        |x, y| (x, y),   |x, y| (-x, y),  |x, y| (x, -y),  |x, y| (-x, -y),
+        |x, y| (y, x),   |x, y| (-y, x),  |x, y| (y, -x),  |x, y| (-y, -x),
+    ];
+    images
+        .iter()
+        .map(|image| {
+            let mut shape: Shape = cells.iter().map(|c| image(c.x, c.y)).collect();
+            let x_min = shape.iter().map(|&(x, _)| x).min().unwrap_or(0);
+            let y_min = shape.iter().map(|&(_, y)| y).min().unwrap_or(0);
+            for (x, y) in &mut shape {
+                (*x, *y) = (*x - x_min, *y - y_min);
+            }
+            shape.sort();
+            shape
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+/// Draws a shape as plaintext rows separated by `/`.
+fn shape_rows(shape: &Shape) -> String {
+    let width = shape.iter().map(|&(x, _)| x + 1).max().unwrap_or(0) as usize;
+    let height = shape.iter().map(|&(_, y)| y + 1).max().unwrap_or(0) as usize;
+    let mut rows = vec![vec!['.'; width]; height];
+    for &(x, y) in shape {
+        rows[y as usize][x as usize] = 'O';
+    }
+    rows.into_iter()
+        .map(String::from_iter)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Cell, Config, State, format::rle};
+
+    fn coords(cells: &[(i32, i32)]) -> Vec<Coords> {
+        cells.iter().map(|&(x, y)| Coords { x, y }).collect()
+    }
+
+    #[test]
+    fn canonical_form_ignores_rotation_and_position() {
+        let glider = coords(&[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+        let turned = coords(&[(10, 10), (10, 11), (12, 11), (11, 12), (10, 12)]);
+        let moved: Vec<_> = glider
+            .iter()
+            .map(|c| Coords {
+                x: c.x - 7,
+                y: c.y + 3,
+            })
+            .collect();
+        assert_eq!(canonical(&glider), canonical(&turned));
+        assert_eq!(canonical(&glider), canonical(&moved));
+    }
+
+    #[test]
+    fn identifies_every_phase() {
+        let census = Census::new();
+        let blinker = coords(&[(5, 4), (5, 5), (5, 6)]);
+        assert_eq!(census.identify(&blinker), Some("blinker"));
+        let glider = coords(&[(0, 0), (2, 0), (1, 1), (2, 1), (1, 2)]);
+        assert_eq!(census.identify(&glider), Some("glider"));
+        let unknown = coords(&[(0, 0), (1, 0), (2, 0), (3, 0)]);
+        assert_eq!(census.identify(&unknown), None);
+    }
+
+    #[test]
+    fn knows_the_repeating_library_patterns() {
+        let census = Census::new();
+        let identify = |name| {
+            let pattern = library::get(name).unwrap();
+            let cells: Vec<_> = pattern.cells.iter().map(|&(coords, _)| coords).collect();
+            census.identify(&cells)
+        };
+        for name in ["pond", "beacon", "pentadecathlon", "hwss"] {
+            assert_eq!(identify(name), Some(name));
+        }
+        for name in ["gosper-gun", "r-pentomino", "diehard"] {
+            assert_eq!(identify(name), None);
+        }
+    }
+
+    #[test]
+    fn splits_objects_by_gap() {
+        let mut state = State::new(&Config::default());
+        // two blocks two cells apart
+        rle::parse("x = 6, y = 2\n2o2b2o$2o2b2o!")
+            .unwrap()
+            .place(&mut state, 10, 10);
+        assert_eq!(components(&state, 0).len(), 2);
+        assert_eq!(components(&state, 1).len(), 2);
+        assert_eq!(components(&state, 2).len(), 1);
+    }
+
+    #[test]
+    fn counts_objects() {
+        let mut state = State::new(&Config::default());
+        let pattern = "x = 14, y = 5\n2o4b3o$2o$9bo$10bo$8b3o!";
+        rle::parse(pattern).unwrap().place(&mut state, 10, 10);
+        state.set_cell(40, 20, Cell::Alive);
+
+        let census = Census::new().count(&state, 0);
+        assert_eq!(census.get("block"), Some(&1));
+        assert_eq!(census.get("blinker"), Some(&1));
+        assert_eq!(census.get("glider"), Some(&1));
+        assert_eq!(census.get("O"), Some(&1));
+        assert_eq!(census.len(), 4);
+    }
+
+    #[test]
+    fn follows_objects_across_edges() {
+        let mut state = State::new(&Config {
+            cols: 20,
+            rows: 20,
+            ..Config::default()
+        });
+        for (x, y) in [(19, 0), (0, 0), (1, 0)] {
+            state.set_cell(x, y, Cell::Alive);
+        }
+        assert_eq!(Census::new().count(&state, 0).get("blinker"), Some(&1));
+    }
+}
@@ -1,4 +1,73 @@
+use std::{fmt, str::FromStr};
+
+use super::{rule::Rule, topology::Topology};
+
+/// Dense engine used for finite boards.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Backend {
+    // One `Cell` per byte, supports every rule.
+    #[default]
+    Cells,
+    // 64 cells per `u64`, supports Life-like rules only. Generations rules fall
+    // back to `Cells`.
+    Bits,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ParseBackendError;
+
 pub struct Config {
     pub cols: u32,
     pub rows: u32,
+    pub rule: Rule,
+    pub topology: Topology,
+    pub backend: Backend,
+    // Number of threads stepping the `Cells` backend in parallel.
+    pub threads: usize,
+    // Number of generations whose statistics are retained, 0 disables them.
+    pub stats: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            cols: 80,
+            rows: 25,
+            rule: Rule::default(),
+            topology: Topology::default(),
+            backend: Backend::default(),
+            threads: 1,
+            stats: 1024,
+        }
+    }
+}
+
+impl FromStr for Backend {
+    type Err = ParseBackendError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "cells" => Ok(Backend::Cells),
+            "bits" => Ok(Backend::Bits),
+            _ => Err(ParseBackendError),
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Backend::Cells => "cells",
+            Backend::Bits => "bits",
+        };
+        f.write_str(name)
+    }
+}
+
+impl fmt::Display for ParseBackendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "backend must be one of cells, bits")
+    }
 }
+
+impl std::error::Error for ParseBackendError {}
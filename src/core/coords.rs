@@ -3,7 +3,7 @@
 //! Naming the dimensions {x, y} results in a more readable API than using
 //! indices of a tuple (x, y).
 
-#[derive(Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Coords {
     pub x: i32,
     pub y: i32,
@@ -0,0 +1,298 @@
+//! Detection of boards that settled into a still life, an oscillator or a
+//! spaceship.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use super::{bounds::Bounds, coords::Coords, simulation::Simulation};
+
+// Number of past generations remembered by default, which is also the longest
+// period that can be detected.
+const DEFAULT_CAPACITY: usize = 512;
+
+/// A board that repeats itself every `period` generations from generation
+/// `start` onwards, translated by (dx,dy). A period of one without translation
+/// is a still life, any translation makes a spaceship.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Cycle {
+    pub start: u64,
+    pub period: u64,
+    pub dx: i32,
+    pub dy: i32,
+}
+
+/// Detects cycles by hashing the board every generation and looking the hash
+/// up in a bounded history of the previous ones.
+///
+/// Cells are hashed relative to the top-left corner of their bounding box, so
+/// that translated boards hash the same and the corners give the displacement.
+///
+/// Observations must be made on consecutive generations: any gap, eg. after a
+/// hyperspeed leap, restarts the detection. Edits that do not change the
+/// generation must be reported with `reset`.
+///
+/// Two boards are assumed equal when their hashes are, the odds of a collision
+/// being negligible for 64 bit hashes.
+pub struct CycleDetector {
+    capacity: usize,
+    cycle: Option<Cycle>,
+    // Board hashes, oldest first, along with their generation.
+    history: VecDeque<(u64, u64)>,
+    // Latest generation of each hash in history, and top-left corner then.
+    generations: HashMap<u64, (u64, Coords)>,
+}
+
+impl CycleDetector {
+    pub fn new(capacity: usize) -> Self {
+        CycleDetector {
+            capacity: capacity.max(1),
+            cycle: None,
+            history: VecDeque::new(),
+            generations: HashMap::new(),
+        }
+    }
+
+    /// Records the current generation of the simulation, returning the cycle
+    /// it belongs to, if any. Observing the same generation twice is a no-op.
+    pub fn observe<S: Simulation + ?Sized>(&mut self, sim: &S) -> Option<Cycle> {
+        let generation = sim.generation();
+        match self.history.back() {
+            Some(&(_, last)) if last == generation => return self.cycle,
+            Some(&(_, last)) if last + 1 != generation => self.reset(),
+            _ => {}
+        }
+
+        let (hash, origin) = board_hash(sim);
+        if self.cycle.is_none() {
+            self.cycle = self.generations.get(&hash).map(|&(start, from)| Cycle {
+                start,
+                period: generation - start,
+                dx: origin.x - from.x,
+                dy: origin.y - from.y,
+            });
+        }
+
+        self.history.push_back((hash, generation));
+        self.generations.insert(hash, (generation, origin));
+        if self.history.len() > self.capacity {
+            let (hash, generation) = self.history.pop_front().expect("history is not empty");
+            // the hash may have been seen again since
+            if self.generations.get(&hash).map(|&(g, _)| g) == Some(generation) {
+                self.generations.remove(&hash);
+            }
+        }
+
+        self.cycle
+    }
+
+    /// Steps the simulation until it settles into a cycle, for at most the
+    /// given number of generations.
+    pub fn run<S: Simulation + ?Sized>(&mut self, sim: &mut S, generations: u64) -> Option<Cycle> {
+        for _ in 0..generations {
+            if self.observe(sim).is_some() {
+                break;
+            }
+            sim.step();
+        }
+        self.observe(sim)
+    }
+
+    /// Returns the cycle found so far, if any.
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    /// Forgets every observation, eg. after the board was edited.
+    pub fn reset(&mut self) {
+        self.cycle = None;
+        self.history.clear();
+        self.generations.clear();
+    }
+}
+
+impl Default for CycleDetector {
+    fn default() -> Self {
+        CycleDetector::new(DEFAULT_CAPACITY)
+    }
+}
+
+/// Hashes the non-dead cells of a simulation relative to the top-left corner
+/// of their bounds, which is returned along with the hash. Cell hashes are
+/// summed up, so that the result does not depend on the iteration order of
+/// the engine.
+fn board_hash<S: Simulation + ?Sized>(sim: &S) -> (u64, Coords) {
+    let origin = match Bounds::from_coords(sim.live_cells().map(|(coords, _)| coords)) {
+        Some(bounds) => Coords {
+            x: bounds.x_min,
+            y: bounds.y_min,
+        },
+        None => Coords { x: 0, y: 0 },
+    };
+    let hash = sim.live_cells().fold(0, |sum: u64, (coords, cell)| {
+        let mut hasher = DefaultHasher::new();
+        (coords.x - origin.x, coords.y - origin.y, cell.state()).hash(&mut hasher);
+        sum.wrapping_add(hasher.finish())
+    });
+    (hash, origin)
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+impl Cycle {
+    pub fn is_spaceship(&self) -> bool {
+        self.dx != 0 || self.dy != 0
+    }
+
+    /// Returns the speed of a spaceship in the usual notation, eg. `c/4
+    /// diagonal` for the glider or `c/2 orthogonal` for the LWSS.
+    pub fn speed(&self) -> Option<String> {
+        if !self.is_spaceship() {
+            return None;
+        }
+        let distance = self.dx.unsigned_abs().max(self.dy.unsigned_abs()) as u64;
+        let divisor = gcd(distance, self.period);
+        let (distance, period) = (distance / divisor, self.period / divisor);
+
+        let c = match distance {
+            1 => "c".to_string(),
+            distance => format!("{distance}c"),
+        };
+        let speed = match period {
+            1 => c,
+            period => format!("{c}/{period}"),
+        };
+        let direction = if self.dx == 0 || self.dy == 0 {
+            "orthogonal"
+        } else if self.dx.abs() == self.dy.abs() {
+            "diagonal"
+        } else {
+            "oblique"
+        };
+        Some(format!("{speed} {direction}"))
+    }
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "stabilised at generation {} with period {}",
+            self.start, self.period
+        )?;
+        if let Some(speed) = self.speed() {
+            write!(f, ", moving by ({},{}) at {speed}", self.dx, self.dy)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Cell, Config, SparseState, State, format::rle, test_support};
+
+    fn sparse(rle: &str) -> SparseState {
+        test_support::sparse_pattern(&rle::parse(rle).unwrap())
+    }
+
+    #[test]
+    fn detects_still_life() {
+        let mut state = State::new(&Config::default());
+        // a pre-block turning into a block
+        for (x, y) in [(10, 10), (11, 10), (10, 11)] {
+            state.set_cell(x, y, Cell::Alive);
+        }
+        let mut detector = CycleDetector::default();
+        detector.run(&mut state, 10);
+        assert_eq!(
+            detector.cycle(),
+            Some(Cycle {
+                start: 1,
+                period: 1,
+                dx: 0,
+                dy: 0
+            })
+        );
+    }
+
+    #[test]
+    fn detects_oscillator_on_sparse_engine() {
+        let mut sparse = test_support::sparse(&[(0, 0), (1, 0), (2, 0)]);
+        let mut detector = CycleDetector::default();
+        detector.run(&mut sparse, 10);
+        let cycle = detector.cycle().unwrap();
+        assert_eq!(
+            cycle,
+            Cycle {
+                start: 0,
+                period: 2,
+                dx: 0,
+                dy: 0
+            }
+        );
+        assert_eq!(
+            cycle.to_string(),
+            "stabilised at generation 0 with period 2"
+        );
+    }
+
+    #[test]
+    fn misses_periods_longer_than_history() {
+        let mut sparse = test_support::sparse(&[(0, 0), (1, 0), (2, 0)]);
+        let mut detector = CycleDetector::new(1);
+        detector.run(&mut sparse, 10);
+        assert_eq!(detector.cycle(), None);
+    }
+
+    #[test]
+    fn restarts_after_gaps_and_resets() {
+        let mut state = State::new(&Config::default());
+        for x in 0..3 {
+            state.set_cell(x, 5, Cell::Alive);
+        }
+        let mut detector = CycleDetector::default();
+        detector.run(&mut state, 10);
+        assert!(detector.cycle().is_some());
+
+        state.set_generation(100);
+        assert_eq!(detector.observe(&state), None);
+
+        detector.reset();
+        assert_eq!(detector.cycle(), None);
+    }
+
+    #[test]
+    fn detects_spaceships() {
+        let mut glider = sparse("x = 3, y = 3\nbo$2bo$3o!");
+        let cycle = CycleDetector::default().run(&mut glider, 10).unwrap();
+        assert_eq!((cycle.period, cycle.dx, cycle.dy), (4, 1, 1));
+        assert_eq!(cycle.speed().as_deref(), Some("c/4 diagonal"));
+
+        let mut lwss = sparse("x = 5, y = 4\nbo2bo$o4b$o3bo$4o!");
+        let cycle = CycleDetector::default().run(&mut lwss, 10).unwrap();
+        assert_eq!((cycle.period, cycle.dx, cycle.dy), (4, -2, 0));
+        assert_eq!(
+            cycle.to_string(),
+            "stabilised at generation 0 with period 4, moving by (-2,0) at c/2 orthogonal"
+        );
+    }
+
+    #[test]
+    fn formats_speeds() {
+        let cycle = |period, dx, dy| Cycle {
+            start: 0,
+            period,
+            dx,
+            dy,
+        };
+        assert_eq!(cycle(2, 0, 0).speed(), None);
+        assert_eq!(cycle(6, 0, 2).speed().as_deref(), Some("c/3 orthogonal"));
+        assert_eq!(cycle(5, 2, 1).speed().as_deref(), Some("2c/5 oblique"));
+        assert_eq!(cycle(1, 1, 0).speed().as_deref(), Some("c orthogonal"));
+    }
+}
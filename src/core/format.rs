@@ -0,0 +1,292 @@
+//! Pattern file formats.
+//!
+//! Each format module reads a file content into a `Pattern` and writes a
+//! `Pattern` back out. Patterns are decoupled from simulations: they can be
+//! placed onto any simulation at an offset, or extracted from the live cells
+//! of one.
+//!
+//! `parse` detects the format from the file content, so that any downloaded
+//! pattern can be loaded regardless of its extension.
+
+pub mod life105;
+pub mod life106;
+pub mod plaintext;
+pub mod rle;
+
+use std::{fmt, fs, io, path::Path};
+
+use super::{
+    bounds::Bounds, cell::Cell, coords::Coords, rule::ParseRuleError, rule::Rule,
+    simulation::Simulation,
+};
+
+/// A pattern with cells relative to its top-left corner, along with the
+/// metadata found in its file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Pattern {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub comments: Vec<String>,
+    pub rule: Option<Rule>,
+    pub width: u32,
+    pub height: u32,
+    // Non-dead cells only.
+    pub cells: Vec<(Coords, Cell)>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    // The mandatory header line is missing or malformed.
+    InvalidHeader,
+    // The rule found in the header or in a comment line is not valid.
+    InvalidRule(ParseRuleError),
+    // An unexpected character was found at the given line (1-based).
+    UnexpectedChar { line: usize, ch: char },
+    // A coordinates line is malformed at the given line (1-based).
+    InvalidCoords { line: usize },
+    // A run reaches beyond the size declared by the header, or beyond the
+    // largest coordinates, at the given line (1-based).
+    TooLarge { line: usize },
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+/// Supported pattern file formats.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    Rle,
+    Plaintext,
+    Life105,
+    Life106,
+}
+
+impl Format {
+    /// Guesses the format of a file from its content.
+    ///
+    /// Life 1.05 and 1.06 files start with their own header, RLE files have an
+    /// `x = ..` header line after their `#` comments, and anything else is
+    /// assumed to be plaintext.
+    pub fn detect(input: &str) -> Format {
+        let mut lines = input.lines().map(str::trim).filter(|line| !line.is_empty());
+        match lines.next() {
+            Some(life105::HEADER) => return Format::Life105,
+            Some(life106::HEADER) => return Format::Life106,
+            _ => {}
+        }
+
+        let header = input
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'));
+        match header {
+            Some(line) if line.starts_with('x') && line.contains('=') => Format::Rle,
+            _ => Format::Plaintext,
+        }
+    }
+
+    /// Guesses the format of a file from its extension: `.rle`, `.cells` or
+    /// `.txt`, and `.lif` or `.life`, taken as Life 1.06.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Format> {
+        let extension = path.as_ref().extension()?.to_str()?;
+        match extension.to_ascii_lowercase().as_str() {
+            "rle" => Some(Format::Rle),
+            "cells" | "txt" => Some(Format::Plaintext),
+            "lif" | "life" => Some(Format::Life106),
+            _ => None,
+        }
+    }
+
+    pub fn parse(self, input: &str) -> Result<Pattern, ParseError> {
+        match self {
+            Format::Rle => rle::parse(input),
+            Format::Plaintext => plaintext::parse(input),
+            Format::Life105 => life105::parse(input),
+            Format::Life106 => life106::parse(input),
+        }
+    }
+
+    pub fn write(self, pattern: &Pattern) -> String {
+        match self {
+            Format::Rle => rle::write(pattern),
+            Format::Plaintext => plaintext::write(pattern),
+            Format::Life105 => life105::write(pattern),
+            Format::Life106 => life106::write(pattern),
+        }
+    }
+}
+
+/// Parses a pattern file of any supported format.
+pub fn parse(input: &str) -> Result<Pattern, ParseError> {
+    Format::detect(input).parse(input)
+}
+
+/// Reads and parses a pattern file of any supported format.
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Pattern, LoadError> {
+    let input = fs::read_to_string(path).map_err(LoadError::Io)?;
+    parse(&input).map_err(LoadError::Parse)
+}
+
+/// Writes a pattern file in the format matching its extension, RLE when the
+/// extension is unknown.
+pub fn save<P: AsRef<Path>>(path: P, pattern: &Pattern) -> io::Result<()> {
+    let format = Format::from_path(&path).unwrap_or(Format::Rle);
+    fs::write(path, format.write(pattern))
+}
+
+impl Pattern {
+    /// Builds a pattern out of non-dead cells, which are shifted so that the
+    /// top-left corner of their bounds is (0,0).
+    pub fn from_cells<I: IntoIterator<Item = (Coords, Cell)>>(cells: I) -> Self {
+        let cells: Vec<_> = cells
+            .into_iter()
+            .filter(|(_, cell)| *cell != Cell::Dead)
+            .collect();
+        let Some(bounds) = Bounds::from_coords(cells.iter().map(|(coords, _)| *coords)) else {
+            return Pattern::default();
+        };
+
+        let cells = cells
+            .into_iter()
+            .map(|(coords, cell)| {
+                let coords = Coords {
+                    x: coords.x - bounds.x_min,
+                    y: coords.y - bounds.y_min,
+                };
+                (coords, cell)
+            })
+            .collect();
+
+        Pattern {
+            width: bounds.width(),
+            height: bounds.height(),
+            cells,
+            ..Pattern::default()
+        }
+    }
+
+    /// Extracts the live cells of a simulation, along with its rule.
+    pub fn from_simulation<S: Simulation + ?Sized>(sim: &S) -> Self {
+        Pattern {
+            rule: Some(*sim.rule()),
+            ..Pattern::from_cells(sim.live_cells())
+        }
+    }
+
+    /// Sets the pattern cells onto a simulation, with the pattern top-left
+    /// corner at (x,y). Cells outside the pattern are left untouched.
+    pub fn place<S: Simulation + ?Sized>(&self, sim: &mut S, x: i32, y: i32) {
+        for (coords, cell) in &self.cells {
+            sim.set_cell(x + coords.x, y + coords.y, *cell);
+        }
+    }
+}
+
+/// Draws the living cells of a pattern as rows of `.` and `alive` characters,
+/// without trailing dead cells.
+fn alive_rows(pattern: &Pattern, alive: char) -> Vec<String> {
+    let mut rows = vec![Vec::new(); pattern.height as usize];
+    for (coords, cell) in &pattern.cells {
+        if *cell != Cell::Alive {
+            continue;
+        }
+        if let Some(row) = rows.get_mut(coords.y as usize) {
+            let x = coords.x as usize;
+            if row.len() <= x {
+                row.resize(x + 1, '.');
+            }
+            row[x] = alive;
+        }
+    }
+    rows.into_iter().map(String::from_iter).collect()
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidHeader => write!(f, "missing or invalid pattern header"),
+            ParseError::InvalidRule(e) => write!(f, "invalid pattern rule: {e}"),
+            ParseError::UnexpectedChar { line, ch } => {
+                write!(f, "unexpected character '{ch}' at line {line}")
+            }
+            ParseError::InvalidCoords { line } => write!(f, "invalid coordinates at line {line}"),
+            ParseError::TooLarge { line } => write!(f, "pattern too large at line {line}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "cannot read pattern: {e}"),
+            LoadError::Parse(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<ParseRuleError> for ParseError {
+    fn from(e: ParseRuleError) -> Self {
+        ParseError::InvalidRule(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Config, State};
+
+    #[test]
+    fn detects_formats_from_content() {
+        let glider = Pattern::from_cells(
+            [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)].map(|(x, y)| (Coords { x, y }, Cell::Alive)),
+        );
+        for format in [
+            Format::Rle,
+            Format::Plaintext,
+            Format::Life105,
+            Format::Life106,
+        ] {
+            let output = format.write(&glider);
+            assert_eq!(Format::detect(&output), format, "{output}");
+
+            let mut state = State::new(&Config::default());
+            parse(&output).unwrap().place(&mut state, 5, 5);
+            assert_eq!(state.population(), 5);
+        }
+    }
+
+    #[test]
+    fn detects_formats_from_extension() {
+        assert_eq!(Format::from_path("glider.RLE"), Some(Format::Rle));
+        assert_eq!(
+            Format::from_path("dir/glider.cells"),
+            Some(Format::Plaintext)
+        );
+        assert_eq!(Format::from_path("glider.lif"), Some(Format::Life106));
+        assert_eq!(Format::from_path("glider"), None);
+    }
+
+    #[test]
+    fn detects_commented_rle() {
+        assert_eq!(
+            Format::detect("#C comment\n\nx = 1, y = 1\no!"),
+            Format::Rle
+        );
+        assert_eq!(Format::detect("!Name: x\nO"), Format::Plaintext);
+        assert_eq!(Format::detect(""), Format::Plaintext);
+    }
+
+    #[test]
+    fn parses_life_files_after_blank_lines() {
+        for input in ["\n  \n#Life 1.05\n*\n", "\n#Life 1.06\n0 0\n"] {
+            assert_eq!(parse(input).map(|pattern| pattern.cells.len()), Ok(1));
+        }
+    }
+}
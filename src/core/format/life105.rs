@@ -0,0 +1,166 @@
+//! Life 1.05 format, blocks of cells placed at given offsets.
+//!
+//! ```txt
+//! #Life 1.05
+//! #D Glider
+//! #N
+//! #P -1 -1
+//! .*.
+//! ..*
+//! ***
+//! ```
+//!
+//! `#D` lines are descriptions, `#N` selects the normal rule B3/S23 and
+//! `#R 23/36` any other in S/B notation. Each `#P x y` line starts a block of
+//! rows whose top-left corner is at (x,y), where `.` is dead and `*` alive.
+
+use super::{ParseError, Pattern, alive_rows};
+use crate::core::{Cell, Coords, Rule};
+
+pub const HEADER: &str = "#Life 1.05";
+
+/// Parses the content of a Life 1.05 file. The cells are shifted so that the
+/// top-left corner of their bounds is (0,0).
+pub fn parse(input: &str) -> Result<Pattern, ParseError> {
+    // leading blank lines are skipped, as when detecting the format
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .skip_while(|(_, line)| line.trim().is_empty());
+    match lines.next() {
+        Some((_, line)) if line.trim() == HEADER => {}
+        _ => return Err(ParseError::InvalidHeader),
+    }
+
+    let (mut comments, mut rule) = (Vec::new(), None);
+    let mut cells = Vec::new();
+    // top-left corner of the current block, and next row in it
+    let (mut block_x, mut y) = (0, 0);
+
+    for (i, line) in lines {
+        let line = line.trim_end();
+        if let Some(description) = line.strip_prefix("#D") {
+            comments.push(description.trim().to_string());
+        } else if line.starts_with("#N") {
+            rule = Some(Rule::default());
+        } else if let Some(sb) = line.strip_prefix("#R") {
+            rule = Some(sb.parse()?);
+        } else if let Some(position) = line.strip_prefix("#P") {
+            let mut values = position.split_whitespace().map(str::parse);
+            let (Some(Ok(x)), Some(Ok(top)), None) = (values.next(), values.next(), values.next())
+            else {
+                return Err(ParseError::InvalidCoords { line: i + 1 });
+            };
+            (block_x, y) = (x, top);
+        } else if !line.starts_with('#') {
+            for (x, ch) in line.chars().enumerate() {
+                match ch {
+                    '.' => {}
+                    '*' => cells.push((
+                        Coords {
+                            x: block_x + x as i32,
+                            y,
+                        },
+                        Cell::Alive,
+                    )),
+                    ch => return Err(ParseError::UnexpectedChar { line: i + 1, ch }),
+                }
+            }
+            y += 1;
+        }
+    }
+
+    Ok(Pattern {
+        comments,
+        rule,
+        ..Pattern::from_cells(cells)
+    })
+}
+
+/// Writes a pattern as Life 1.05, in a single block at (0,0). Name and author
+/// become descriptions, and the number of states of the rule is lost.
+pub fn write(pattern: &Pattern) -> String {
+    let mut output = format!("{HEADER}\n");
+    let descriptions = pattern
+        .name
+        .iter()
+        .chain(&pattern.author)
+        .chain(&pattern.comments);
+    for description in descriptions {
+        output.push_str(&format!("#D {description}\n"));
+    }
+
+    match pattern.rule {
+        None => {}
+        Some(rule) if rule == Rule::default() => output.push_str("#N\n"),
+        Some(rule) => {
+            let counts = |is_count: &dyn Fn(u8) -> bool| -> String {
+                (0..=8)
+                    .filter(|&n| is_count(n))
+                    .map(|n| char::from(b'0' + n))
+                    .collect()
+            };
+            let survival = counts(&|n| rule.is_survival(n));
+            let birth = counts(&|n| rule.is_birth(n));
+            output.push_str(&format!("#R {survival}/{birth}\n"));
+        }
+    }
+
+    output.push_str("#P 0 0\n");
+    for row in alive_rows(pattern, '*') {
+        output.push_str(&row);
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_blocks_and_rule() {
+        let input = "#Life 1.05\n#D Two blinkers\n#R 23/36\n#P -1 -1\n***\n#P 3 2\n*\n*\n*\n";
+        let pattern = parse(input).unwrap();
+        assert_eq!(pattern.comments, vec!["Two blinkers"]);
+        assert_eq!(pattern.rule, Some(Rule::new(&[3, 6], &[2, 3])));
+        assert_eq!((pattern.width, pattern.height), (5, 6));
+        assert!(
+            pattern
+                .cells
+                .contains(&(Coords { x: 0, y: 0 }, Cell::Alive))
+        );
+        assert!(
+            pattern
+                .cells
+                .contains(&(Coords { x: 4, y: 5 }, Cell::Alive))
+        );
+    }
+
+    #[test]
+    fn writes_what_it_parses() {
+        let input = "#Life 1.05\n#D Glider\n#N\n#P 0 0\n.*\n..*\n***\n";
+        let pattern = parse(input).unwrap();
+        assert_eq!(pattern.rule, Some(Rule::default()));
+        assert_eq!(write(&pattern), input);
+
+        let pattern = Pattern {
+            rule: Some(Rule::new(&[3, 6], &[2, 3])),
+            ..pattern
+        };
+        assert!(write(&pattern).contains("#R 23/36\n"));
+    }
+
+    #[test]
+    fn rejects_invalid_lines() {
+        assert_eq!(parse("#P 0 0\n*"), Err(ParseError::InvalidHeader));
+        assert_eq!(
+            parse("#Life 1.05\n#P 0\n*"),
+            Err(ParseError::InvalidCoords { line: 2 })
+        );
+        assert_eq!(
+            parse("#Life 1.05\n#P 0 0\n.O"),
+            Err(ParseError::UnexpectedChar { line: 3, ch: 'O' })
+        );
+    }
+}
@@ -0,0 +1,82 @@
+//! Life 1.06 format, a plain list of living cell coordinates.
+//!
+//! ```txt
+//! #Life 1.06
+//! 0 -1
+//! 1 0
+//! -1 1
+//! 0 1
+//! 1 1
+//! ```
+//!
+//! Coordinates can be negative, the format has no rule and no metadata.
+
+use super::{ParseError, Pattern};
+use crate::core::{Cell, Coords};
+
+pub const HEADER: &str = "#Life 1.06";
+
+/// Parses the content of a Life 1.06 file. The cells are shifted so that the
+/// top-left corner of their bounds is (0,0).
+pub fn parse(input: &str) -> Result<Pattern, ParseError> {
+    // leading blank lines are skipped, as when detecting the format
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .skip_while(|(_, line)| line.trim().is_empty());
+    match lines.next() {
+        Some((_, line)) if line.trim() == HEADER => {}
+        _ => return Err(ParseError::InvalidHeader),
+    }
+
+    let mut cells = Vec::new();
+    for (i, line) in lines {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut values = line.split_whitespace().map(str::parse);
+        let (Some(Ok(x)), Some(Ok(y)), None) = (values.next(), values.next(), values.next()) else {
+            return Err(ParseError::InvalidCoords { line: i + 1 });
+        };
+        cells.push((Coords { x, y }, Cell::Alive));
+    }
+
+    Ok(Pattern::from_cells(cells))
+}
+
+/// Writes the living cells of a pattern as Life 1.06, metadata is lost.
+pub fn write(pattern: &Pattern) -> String {
+    let mut output = format!("{HEADER}\n");
+    for (Coords { x, y }, cell) in &pattern.cells {
+        if *cell == Cell::Alive {
+            output.push_str(&format!("{x} {y}\n"));
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_negative_coords() {
+        let pattern = parse("#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n").unwrap();
+        assert_eq!((pattern.width, pattern.height), (3, 3));
+        assert_eq!(pattern.cells[0], (Coords { x: 1, y: 0 }, Cell::Alive));
+        assert_eq!(write(&pattern), "#Life 1.06\n1 0\n2 1\n0 2\n1 2\n2 2\n");
+    }
+
+    #[test]
+    fn rejects_invalid_lines() {
+        assert_eq!(parse("0 0\n"), Err(ParseError::InvalidHeader));
+        assert_eq!(
+            parse("#Life 1.06\n0 0\n1\n"),
+            Err(ParseError::InvalidCoords { line: 3 })
+        );
+        assert_eq!(
+            parse("#Life 1.06\n0 a\n"),
+            Err(ParseError::InvalidCoords { line: 2 })
+        );
+    }
+}
@@ -0,0 +1,120 @@
+//! Plaintext format, used by the `.cells` files of the LifeWiki.
+//!
+//! ```txt
+//! !Name: Glider
+//! !Author: Richard K. Guy
+//! !The smallest, most common, and first discovered spaceship.
+//! .O
+//! ..O
+//! OOO
+//! ```
+//!
+//! Lines starting with `!` are comments, the others are rows of cells where
+//! `.` is dead and `O` is alive. Trailing dead cells of a row can be omitted.
+//! The format has no rule and no dying states.
+
+use super::{ParseError, Pattern, alive_rows};
+use crate::core::{Cell, Coords};
+
+/// Parses the content of a plaintext file. `*` is accepted as a living cell,
+/// as some older files use it.
+pub fn parse(input: &str) -> Result<Pattern, ParseError> {
+    let mut pattern = Pattern::default();
+    let mut y = 0;
+
+    for (i, line) in input.lines().enumerate() {
+        if let Some(comment) = line.strip_prefix('!') {
+            let comment = comment.trim();
+            if let Some(name) = comment.strip_prefix("Name:") {
+                pattern.name = Some(name.trim().to_string());
+            } else if let Some(author) = comment.strip_prefix("Author:") {
+                pattern.author = Some(author.trim().to_string());
+            } else {
+                pattern.comments.push(comment.to_string());
+            }
+            continue;
+        }
+
+        let line = line.trim_end();
+        for (x, ch) in line.chars().enumerate() {
+            match ch {
+                '.' => {}
+                'O' | '*' => pattern.cells.push((Coords { x: x as i32, y }, Cell::Alive)),
+                ch => return Err(ParseError::UnexpectedChar { line: i + 1, ch }),
+            }
+        }
+        pattern.width = pattern.width.max(line.len() as u32);
+        y += 1;
+    }
+
+    pattern.height = y as u32;
+    Ok(pattern)
+}
+
+/// Writes a pattern as plaintext. Dying cells are written as dead.
+pub fn write(pattern: &Pattern) -> String {
+    let mut output = String::new();
+    if let Some(name) = &pattern.name {
+        output.push_str(&format!("!Name: {name}\n"));
+    }
+    if let Some(author) = &pattern.author {
+        output.push_str(&format!("!Author: {author}\n"));
+    }
+    for comment in &pattern.comments {
+        output.push_str(&format!("!{comment}\n"));
+    }
+
+    for row in alive_rows(pattern, 'O') {
+        output.push_str(&row);
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GLIDER: &str = "\
+!Name: Glider
+!Author: Richard K. Guy
+!The smallest, most common, and first discovered spaceship.
+.O
+..O
+OOO
+";
+
+    #[test]
+    fn parses_comments_and_rows() {
+        let pattern = parse(GLIDER).unwrap();
+        assert_eq!(pattern.name.as_deref(), Some("Glider"));
+        assert_eq!(pattern.author.as_deref(), Some("Richard K. Guy"));
+        assert_eq!(pattern.comments.len(), 1);
+        assert_eq!(pattern.rule, None);
+        assert_eq!((pattern.width, pattern.height), (3, 3));
+        assert_eq!(pattern.cells.len(), 5);
+        assert!(
+            pattern
+                .cells
+                .contains(&(Coords { x: 2, y: 1 }, Cell::Alive))
+        );
+    }
+
+    #[test]
+    fn writes_what_it_parses() {
+        assert_eq!(write(&parse(GLIDER).unwrap()), GLIDER);
+
+        let with_empty_rows = "O\n\n\n..*\n";
+        let pattern = parse(with_empty_rows).unwrap();
+        assert_eq!((pattern.width, pattern.height), (3, 4));
+        assert_eq!(write(&pattern), "O\n\n\n..O\n");
+    }
+
+    #[test]
+    fn rejects_unknown_cells() {
+        assert_eq!(
+            parse("!comment\n.O\nOx"),
+            Err(ParseError::UnexpectedChar { line: 3, ch: 'x' })
+        );
+    }
+}
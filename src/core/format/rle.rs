@@ -0,0 +1,395 @@
+//! Run Length Encoded format, the most common format for Life patterns.
+//!
+//! ```txt
+//! #N Glider
+//! #O Richard K. Guy
+//! #C The smallest, most common, and first discovered spaceship.
+//! x = 3, y = 3, rule = B3/S23
+//! bo$2bo$3o!
+//! ```
+//!
+//! Comment lines start with `#`, followed by the header line with pattern size
+//! and optional rule. A `#r` comment line may give the rule in S/B notation
+//! instead, eg. `#r 23/3`, the header rule taking precedence.
+//!
+//! The body lists runs of cells, row by row: `b` is a dead cell, `o` a living
+//! one, `$` ends a row and `!` ends the pattern. A number before a tag repeats
+//! it. Generations patterns use `.` for dead cells and `A` to `X` for the
+//! following states, then `pA` to `yO` for the states beyond the 24th.
+
+use super::{ParseError, Pattern};
+use crate::core::{Cell, Coords};
+
+// Lines of the pattern body should not exceed this length.
+const LINE_LENGTH: usize = 70;
+// States written with a single letter, `A` to `X`. Higher ones are prefixed
+// with `p` to `y`, each prefix adding this many states.
+const STATE_LETTERS: u16 = 24;
+
+/// Parses the content of an RLE file.
+pub fn parse(input: &str) -> Result<Pattern, ParseError> {
+    let mut pattern = Pattern::default();
+    let mut lines = input.lines().enumerate();
+
+    // Comments and header
+    loop {
+        let Some((_, line)) = lines.next() else {
+            return Err(ParseError::InvalidHeader);
+        };
+        let line = line.trim();
+        if let Some(comment) = line.strip_prefix('#') {
+            parse_comment(comment, &mut pattern)?;
+        } else if !line.is_empty() {
+            parse_header(line, &mut pattern)?;
+            break;
+        }
+    }
+
+    // Body, whose living cells must lie within the declared size
+    let (width, height) = (pattern.width as i64, pattern.height as i64);
+    let (mut x, mut y): (i32, i32) = (0, 0);
+    let mut count: i32 = 0;
+    let mut prefix: u16 = 0;
+    let multi_state = pattern.rule.is_some_and(|rule| rule.states() > 2);
+    'body: for (i, line) in lines {
+        for ch in line.chars() {
+            if let Some(digit) = ch.to_digit(10) {
+                count = count.saturating_mul(10).saturating_add(digit as i32);
+                continue;
+            }
+            if multi_state && ('p'..='y').contains(&ch) {
+                prefix = ch as u16 - 'p' as u16 + 1;
+                continue;
+            }
+
+            let run = count.max(1);
+            count = 0;
+            let too_large = || ParseError::TooLarge { line: i + 1 };
+            let cell = match ch {
+                '!' => break 'body,
+                '$' => {
+                    (x, y) = (0, y.checked_add(run).ok_or_else(too_large)?);
+                    continue;
+                }
+                'b' | '.' => Cell::Dead,
+                'o' => Cell::Alive,
+                'A'..='X' => {
+                    let state = prefix * STATE_LETTERS + (ch as u16 - 'A' as u16) + 1;
+                    prefix = 0;
+                    let state = u8::try_from(state)
+                        .map_err(|_| ParseError::UnexpectedChar { line: i + 1, ch })?;
+                    Cell::from_state(state)
+                }
+                // any other letter is a living cell in two-state patterns
+                'a'..='z' => Cell::Alive,
+                ch if ch.is_whitespace() => continue,
+                ch => return Err(ParseError::UnexpectedChar { line: i + 1, ch }),
+            };
+
+            let end = x.checked_add(run).ok_or_else(too_large)?;
+            if cell != Cell::Dead {
+                if end as i64 > width || y as i64 >= height {
+                    return Err(too_large());
+                }
+                pattern
+                    .cells
+                    .extend((x..end).map(|x| (Coords { x, y }, cell)));
+            }
+            x = end;
+        }
+    }
+
+    Ok(pattern)
+}
+
+/// Reads `#N` name, `#O` author, `#C` comment and `#r` rule lines, ignoring
+/// the others.
+fn parse_comment(comment: &str, pattern: &mut Pattern) -> Result<(), ParseError> {
+    let mut chars = comment.chars();
+    let kind = chars.next();
+    let text = chars.as_str().trim().to_string();
+    match kind {
+        Some('N') => pattern.name = Some(text),
+        Some('O') => pattern.author = Some(text),
+        Some('C' | 'c') => pattern.comments.push(text),
+        Some('r') => pattern.rule = Some(text.parse()?),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Reads the `x = 3, y = 3, rule = B3/S23` header, where the rule is optional.
+/// Unknown keys are ignored, as well as bounded grid suffixes of the rule, eg.
+/// `B3/S23:T80,25`.
+fn parse_header(header: &str, pattern: &mut Pattern) -> Result<(), ParseError> {
+    let (mut width, mut height) = (None, None);
+    for (key, value) in header.split(',').filter_map(|item| item.split_once('=')) {
+        let value = value.trim();
+        match key.trim() {
+            "x" => width = value.parse().ok(),
+            "y" => height = value.parse().ok(),
+            "rule" => {
+                let rule = value.split(':').next().unwrap_or_default();
+                pattern.rule = Some(rule.parse()?);
+            }
+            _ => {}
+        }
+    }
+
+    let (Some(width), Some(height)) = (width, height) else {
+        return Err(ParseError::InvalidHeader);
+    };
+    pattern.width = width;
+    pattern.height = height;
+    Ok(())
+}
+
+/// Writes a pattern as RLE, wrapping the body lines at 70 characters.
+pub fn write(pattern: &Pattern) -> String {
+    let mut output = String::new();
+    if let Some(name) = &pattern.name {
+        output.push_str(&format!("#N {name}\n"));
+    }
+    if let Some(author) = &pattern.author {
+        output.push_str(&format!("#O {author}\n"));
+    }
+    for comment in &pattern.comments {
+        output.push_str(&format!("#C {comment}\n"));
+    }
+
+    let rule = pattern.rule.unwrap_or_default();
+    output.push_str(&format!(
+        "x = {}, y = {}, rule = {}\n",
+        pattern.width, pattern.height, rule
+    ));
+
+    let tag = |state: u16| match (rule.states(), state) {
+        (2, 0) => "b".to_string(),
+        (2, _) => "o".to_string(),
+        (_, 0) => ".".to_string(),
+        (_, state) => {
+            let (prefix, letter) = ((state - 1) / STATE_LETTERS, (state - 1) % STATE_LETTERS);
+            let letter = char::from(b'A' + letter as u8);
+            match prefix {
+                0 => letter.to_string(),
+                prefix => format!("{}{letter}", char::from(b'p' + prefix as u8 - 1)),
+            }
+        }
+    };
+    let run = |count: usize, tag: &str| match count {
+        1 => tag.to_string(),
+        count => format!("{count}{tag}"),
+    };
+
+    // Runs are computed from the non-dead cells alone, so that sparse patterns
+    // spanning a large area are cheap to write. Trailing dead cells are
+    // implied by the end of the row.
+    let (width, height) = (pattern.width as i64, pattern.height as i64);
+    let mut cells: Vec<_> = pattern
+        .cells
+        .iter()
+        .filter(|(coords, cell)| {
+            *cell != Cell::Dead
+                && (0..width).contains(&(coords.x as i64))
+                && (0..height).contains(&(coords.y as i64))
+        })
+        .map(|(coords, cell)| (coords.y, coords.x, cell.state()))
+        .collect();
+    cells.sort_unstable();
+    cells.dedup_by_key(|&mut (y, x, _)| (y, x));
+
+    let mut runs: Vec<(usize, String)> = Vec::new();
+    let mut push = |count: usize, tag: String| match runs.last_mut() {
+        Some((n, last)) if *last == tag => *n += count,
+        _ => runs.push((count, tag)),
+    };
+    let (mut x, mut y) = (0, 0);
+    for (cell_y, cell_x, state) in cells {
+        if cell_y > y {
+            push((cell_y - y) as usize, "$".to_string());
+            (x, y) = (0, cell_y);
+        }
+        if cell_x > x {
+            push((cell_x - x) as usize, tag(0));
+        }
+        push(1, tag(state));
+        x = cell_x + 1;
+    }
+    let mut tokens: Vec<_> = runs.into_iter().map(|(n, tag)| run(n, &tag)).collect();
+    tokens.push("!".to_string());
+
+    let mut line = String::new();
+    for token in tokens {
+        if line.len() + token.len() > LINE_LENGTH {
+            output.push_str(&line);
+            output.push('\n');
+            line.clear();
+        }
+        line.push_str(&token);
+    }
+    output.push_str(&line);
+    output.push('\n');
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Config, Rule, Simulation, State};
+
+    const GLIDER: &str = "\
+#N Glider
+#O Richard K. Guy
+#C The smallest, most common, and first discovered spaceship.
+x = 3, y = 3, rule = B3/S23
+bo$2bo$3o!
+";
+
+    fn sorted_cells(pattern: &Pattern) -> Vec<(i32, i32, u16)> {
+        let mut cells: Vec<_> = pattern
+            .cells
+            .iter()
+            .map(|(coords, cell)| (coords.x, coords.y, cell.state()))
+            .collect();
+        cells.sort();
+        cells
+    }
+
+    #[test]
+    fn parses_comments_header_and_body() {
+        let pattern = parse(GLIDER).unwrap();
+        assert_eq!(pattern.name.as_deref(), Some("Glider"));
+        assert_eq!(pattern.author.as_deref(), Some("Richard K. Guy"));
+        assert_eq!(pattern.comments.len(), 1);
+        assert_eq!(pattern.rule, Some(Rule::default()));
+        assert_eq!((pattern.width, pattern.height), (3, 3));
+        assert_eq!(
+            sorted_cells(&pattern),
+            vec![(0, 2, 1), (1, 0, 1), (1, 2, 1), (2, 1, 1), (2, 2, 1)]
+        );
+    }
+
+    #[test]
+    fn parses_runs_spanning_lines_and_empty_rows() {
+        let pattern = parse("x = 12, y = 4\n10b\n2o2$\no!ignored").unwrap();
+        assert_eq!(pattern.rule, None);
+        assert_eq!(
+            sorted_cells(&pattern),
+            vec![(0, 2, 1), (10, 0, 1), (11, 0, 1)]
+        );
+    }
+
+    #[test]
+    fn parses_generations_states() {
+        let pattern = parse("x = 3, y = 1, rule = B2/S/C3\n.AB!").unwrap();
+        assert_eq!(sorted_cells(&pattern), vec![(1, 0, 1), (2, 0, 2)]);
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert_eq!(parse("#C no header"), Err(ParseError::InvalidHeader));
+        assert_eq!(parse("x = 3\nooo!"), Err(ParseError::InvalidHeader));
+        assert!(matches!(
+            parse("x = 1, y = 1, rule = B9/S"),
+            Err(ParseError::InvalidRule(_))
+        ));
+        assert_eq!(
+            parse("x = 1, y = 1\n\no*!"),
+            Err(ParseError::UnexpectedChar { line: 3, ch: '*' })
+        );
+    }
+
+    #[test]
+    fn rejects_runs_beyond_coords_range() {
+        assert_eq!(
+            parse("x = 1, y = 1\n2147483647b2o!"),
+            Err(ParseError::TooLarge { line: 2 })
+        );
+        assert_eq!(
+            parse("x = 1, y = 1\n2147483647$$o!"),
+            Err(ParseError::TooLarge { line: 2 })
+        );
+    }
+
+    #[test]
+    fn rejects_runs_beyond_declared_size() {
+        assert_eq!(
+            parse("x = 1, y = 1\n2147483646o!"),
+            Err(ParseError::TooLarge { line: 2 })
+        );
+        assert_eq!(
+            parse("x = 2, y = 1\no$o!"),
+            Err(ParseError::TooLarge { line: 2 })
+        );
+        // dead runs and trailing row ends are harmless
+        assert!(parse("x = 2, y = 1\n2o5b3$!").is_ok());
+    }
+
+    #[test]
+    fn parses_rule_comment_lines() {
+        let pattern = parse("#r 23/36\nx = 3, y = 1\n3o!").unwrap();
+        assert_eq!(pattern.rule, Some("B36/S23".parse().unwrap()));
+        let pattern = parse("#r 23/36\nx = 3, y = 1, rule = B3/S23\n3o!").unwrap();
+        assert_eq!(pattern.rule, Some(Rule::default()));
+    }
+
+    #[test]
+    fn writes_far_apart_cells() {
+        let far = 1 << 30;
+        let cells = [(0, 0), (far, far)].map(|(x, y)| (Coords { x, y }, Cell::Alive));
+        let output = write(&Pattern::from_cells(cells));
+        assert!(output.ends_with(&format!("o{far}${far}bo!\n")));
+    }
+
+    #[test]
+    fn writes_what_it_parses() {
+        let pattern = parse(GLIDER).unwrap();
+        assert_eq!(write(&pattern), GLIDER);
+
+        let pattern = parse("x = 3, y = 3, rule = B2/S/C4\nA$$.BC!").unwrap();
+        assert_eq!(write(&pattern), "x = 3, y = 3, rule = B2/S/C4\nA2$.BC!\n");
+    }
+
+    #[test]
+    fn writes_every_state_of_the_largest_rule() {
+        let rule: Rule = "B2/S/C255".parse().unwrap();
+        let cells = (1..=254).map(|state| {
+            (
+                Coords {
+                    x: state as i32 - 1,
+                    y: 0,
+                },
+                Cell::from_state(state),
+            )
+        });
+        let pattern = Pattern {
+            rule: Some(rule),
+            ..Pattern::from_cells(cells)
+        };
+        let output = write(&pattern);
+        assert!(output.contains("pA") && output.ends_with("yN!\n"));
+        assert_eq!(
+            sorted_cells(&parse(&output).unwrap()),
+            sorted_cells(&pattern)
+        );
+    }
+
+    #[test]
+    fn wraps_long_lines() {
+        let cells = (0..100).map(|x| (Coords { x: 2 * x, y: 0 }, Cell::Alive));
+        let output = write(&Pattern::from_cells(cells));
+        assert!(output.lines().all(|line| line.len() <= LINE_LENGTH));
+        assert_eq!(parse(&output).unwrap().cells.len(), 100);
+    }
+
+    #[test]
+    fn places_and_extracts_state_cells() {
+        let mut state = State::new(&Config::default());
+        parse(GLIDER).unwrap().place(&mut state, 10, 5);
+        assert_eq!(state.get_cell(11, 5), Cell::Alive);
+        assert_eq!(state.population(), 5);
+
+        let output = write(&Pattern::from_simulation(&state));
+        assert_eq!(output, "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n");
+    }
+}
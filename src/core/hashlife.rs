@@ -0,0 +1,678 @@
+//! HashLife represents the unbounded plane as a quadtree, where each node of
+//! level `n` is a square of `2^n x 2^n` cells split into four quadrants of level
+//! `n-1`, down to the single cells at level 0.
+//!
+//! ```txt
+//! +----+----+
+//! | nw | ne |
+//! +----+----+
+//! | sw | se |
+//! +----+----+
+//! ```
+//!
+//! Nodes are canonical: two squares with the same content are the very same
+//! node, which is found through a hash map before creating a new one. Given a
+//! node of level `n`, the content of its centre square of level `n-1` after
+//! `2^j` generations (`j <= n-2`) depends on the node only, so it is computed
+//! once and memoized. Repetitive patterns, both in space and time, collapse
+//! into a handful of nodes and the simulation can leap `2^k` generations at a
+//! time.
+//!
+//! Canonical nodes and memoized results are only released by a garbage
+//! collection, which runs between steps when the number of nodes exceeds the
+//! configured cap. A leap exceeding the cap on its way is abandoned, then done
+//! again as two leaps of half as many generations, collecting garbage in
+//! between.
+
+use std::{collections::HashMap, fmt};
+
+use super::{
+    cell::Cell, config::Config, coords::Coords, rule::Rule, simulation::Simulation, state::State,
+};
+
+type NodeId = u32;
+
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+const DEFAULT_MAX_NODES: usize = 1 << 22;
+
+#[derive(Clone, Copy)]
+struct Node {
+    level: u8,
+    population: u64,
+    // Quadrants, leaves (level 0) have none and point to `DEAD`.
+    nw: NodeId,
+    ne: NodeId,
+    sw: NodeId,
+    se: NodeId,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum HashLifeError {
+    // Generations rules and rules with birth on 0 neighbors are not supported:
+    // the former needs more than two states per cell, the latter turns the
+    // infinite empty plane alive.
+    UnsupportedRule,
+}
+
+pub struct HashLife {
+    generation: u64,
+    rule: Rule,
+    max_nodes: usize,
+
+    nodes: Vec<Node>,
+    canonical: HashMap<[NodeId; 4], NodeId>,
+    memo: HashMap<(NodeId, u8), NodeId>,
+
+    // Number of nodes beyond which the current leap is abandoned, none when
+    // it must complete.
+    leap_limit: Option<usize>,
+    // Set once the current leap exceeded its limit.
+    leap_abandoned: bool,
+
+    root: NodeId,
+    // Coordinates of the root top-left cell.
+    origin_x: i64,
+    origin_y: i64,
+}
+
+impl HashLife {
+    pub fn new(rule: Rule) -> Result<Self, HashLifeError> {
+        Self::with_max_nodes(rule, DEFAULT_MAX_NODES)
+    }
+
+    /// Creates an empty universe that collects garbage whenever it holds more
+    /// than `max_nodes` nodes, between leaps or by splitting a leap.
+    pub fn with_max_nodes(rule: Rule, max_nodes: usize) -> Result<Self, HashLifeError> {
+        if rule.states() > 2 || rule.is_birth(0) {
+            return Err(HashLifeError::UnsupportedRule);
+        }
+
+        let leaf = |population| Node {
+            level: 0,
+            population,
+            nw: DEAD,
+            ne: DEAD,
+            sw: DEAD,
+            se: DEAD,
+        };
+        let mut hashlife = HashLife {
+            generation: 0,
+            rule,
+            max_nodes,
+            nodes: vec![leaf(0), leaf(1)],
+            canonical: HashMap::new(),
+            memo: HashMap::new(),
+            leap_limit: None,
+            leap_abandoned: false,
+            root: DEAD,
+            origin_x: 0,
+            origin_y: 0,
+        };
+        hashlife.root = hashlife.empty(3);
+        hashlife.origin_x = -4;
+        hashlife.origin_y = -4;
+        Ok(hashlife)
+    }
+
+    /// Builds a universe holding the living cells of another simulation.
+    pub fn from_simulation<S: Simulation + ?Sized>(sim: &S) -> Result<Self, HashLifeError> {
+        let mut hashlife = Self::new(*sim.rule())?;
+        for (coords, cell) in sim.live_cells() {
+            if matches!(cell, Cell::Alive) {
+                hashlife.set_cell(coords.x, coords.y, cell);
+            }
+        }
+        hashlife.generation = sim.generation();
+        Ok(hashlife)
+    }
+
+    /// Creates a dense `State` holding the living cells of the universe, which
+    /// are wrapped onto the board according to the configured topology.
+    pub fn to_state(&self, config: &Config) -> State {
+        let mut state = State::new(config);
+        for (coords, cell) in self.live_cells() {
+            state.set_cell(coords.x, coords.y, cell);
+        }
+        state.generation = self.generation;
+        state
+    }
+
+    /// Number of nodes currently allocated.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Advance the universe by `2^k` generations at once.
+    pub fn step_pow2(&mut self, k: u8) {
+        if self.nodes.len() > self.max_nodes {
+            self.collect_garbage();
+        }
+
+        // The result of a node is its centre half, that cells reach at most at
+        // light speed. Keeping the pattern within the centre quarter of a root
+        // of level `k+3` or more guarantees no cell is left out.
+        loop {
+            let root = self.nodes[self.root as usize];
+            let centre = self.centre(self.root);
+            let inner = self.centre(centre);
+            if root.level >= k + 3 && self.nodes[inner as usize].population == root.population {
+                break;
+            }
+            self.expand();
+        }
+
+        // a single generation completes whatever the cap, and patterns larger
+        // than the cap get room to grow
+        self.leap_limit = (k > 0).then(|| self.max_nodes.max(2 * self.nodes.len()));
+        self.leap_abandoned = false;
+        let root = self.successor(self.root, k);
+        self.leap_limit = None;
+        if self.leap_abandoned {
+            self.collect_garbage();
+            self.step_pow2(k - 1);
+            self.step_pow2(k - 1);
+            return;
+        }
+
+        let level = self.nodes[self.root as usize].level;
+        let offset = 1i64 << (level - 2);
+        self.root = root;
+        self.origin_x += offset;
+        self.origin_y += offset;
+        self.generation += 1 << k;
+
+        if self.nodes.len() > self.max_nodes {
+            self.collect_garbage();
+        }
+    }
+
+    /// Returns the canonical node made of the given quadrants.
+    fn join(&mut self, nw: NodeId, ne: NodeId, sw: NodeId, se: NodeId) -> NodeId {
+        if let Some(&id) = self.canonical.get(&[nw, ne, sw, se]) {
+            return id;
+        }
+        let population = [nw, ne, sw, se]
+            .iter()
+            .map(|&q| self.nodes[q as usize].population)
+            .sum();
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(Node {
+            level: self.nodes[nw as usize].level + 1,
+            population,
+            nw,
+            ne,
+            sw,
+            se,
+        });
+        self.canonical.insert([nw, ne, sw, se], id);
+        id
+    }
+
+    /// Returns the canonical empty node of the given level.
+    fn empty(&mut self, level: u8) -> NodeId {
+        (0..level).fold(DEAD, |empty, _| self.join(empty, empty, empty, empty))
+    }
+
+    fn quadrants(&self, id: NodeId) -> [NodeId; 4] {
+        let node = self.nodes[id as usize];
+        [node.nw, node.ne, node.sw, node.se]
+    }
+
+    /// Returns the centre square of a node, one level below it.
+    fn centre(&mut self, id: NodeId) -> NodeId {
+        let [nw, ne, sw, se] = self.quadrants(id);
+        let (nw, ne, sw, se) = (
+            self.quadrants(nw)[3],
+            self.quadrants(ne)[2],
+            self.quadrants(sw)[1],
+            self.quadrants(se)[0],
+        );
+        self.join(nw, ne, sw, se)
+    }
+
+    /// Doubles the root size keeping the current root in its centre.
+    fn expand(&mut self) {
+        let level = self.nodes[self.root as usize].level;
+        let empty = self.empty(level - 1);
+        let [nw, ne, sw, se] = self.quadrants(self.root);
+
+        let nw = self.join(empty, empty, empty, nw);
+        let ne = self.join(empty, empty, ne, empty);
+        let sw = self.join(empty, sw, empty, empty);
+        let se = self.join(se, empty, empty, empty);
+        self.root = self.join(nw, ne, sw, se);
+
+        let offset = 1i64 << (level - 1);
+        self.origin_x -= offset;
+        self.origin_y -= offset;
+    }
+
+    /// Returns the centre square of a node of level `n` after `2^j`
+    /// generations, with `j <= n-2`.
+    ///
+    /// The node is split into nine overlapping squares of level `n-1`, whose
+    /// centres are regrouped into four squares of level `n-1` again. Each of
+    /// the two passes can advance time by up to `2^(n-3)` generations.
+    ///
+    /// ```txt
+    /// +----+----+----+
+    /// | 00 | 01 | 02 |
+    /// +----+----+----+
+    /// | 10 | 11 | 12 |
+    /// +----+----+----+
+    /// | 20 | 21 | 22 |
+    /// +----+----+----+
+    /// ```
+    fn successor(&mut self, id: NodeId, j: u8) -> NodeId {
+        let node = self.nodes[id as usize];
+        if node.population == 0 {
+            return self.empty(node.level - 1);
+        }
+        if let Some(&result) = self.memo.get(&(id, j)) {
+            return result;
+        }
+        if self
+            .leap_limit
+            .is_some_and(|limit| self.nodes.len() > limit)
+        {
+            self.leap_abandoned = true;
+        }
+        // results computed once the leap is abandoned are wrong, none is kept
+        if self.leap_abandoned {
+            return DEAD;
+        }
+
+        let result = if node.level == 2 {
+            self.successor_base(id)
+        } else {
+            let [a, b, c, d] = self.quadrants(id);
+            let [_, a_ne, a_sw, a_se] = self.quadrants(a);
+            let [b_nw, _, b_sw, b_se] = self.quadrants(b);
+            let [c_nw, c_ne, _, c_se] = self.quadrants(c);
+            let [d_nw, d_ne, d_sw, _] = self.quadrants(d);
+
+            let squares = [
+                a,
+                self.join(a_ne, b_nw, a_se, b_sw),
+                b,
+                self.join(a_sw, a_se, c_nw, c_ne),
+                self.join(a_se, b_sw, c_ne, d_nw),
+                self.join(b_sw, b_se, d_nw, d_ne),
+                c,
+                self.join(c_ne, d_nw, c_se, d_sw),
+                d,
+            ];
+
+            // the first pass advances time only when leaping the largest step
+            let full_step = j == node.level - 2;
+            let mut centres = [DEAD; 9];
+            for (centre, &square) in centres.iter_mut().zip(squares.iter()) {
+                *centre = if full_step {
+                    self.successor(square, node.level - 3)
+                } else {
+                    self.centre(square)
+                };
+            }
+
+            let second_j = if full_step { node.level - 3 } else { j };
+            let [s00, s01, s02, s10, s11, s12, s20, s21, s22] = centres;
+            let nw = self.join(s00, s01, s10, s11);
+            let ne = self.join(s01, s02, s11, s12);
+            let sw = self.join(s10, s11, s20, s21);
+            let se = self.join(s11, s12, s21, s22);
+            let nw = self.successor(nw, second_j);
+            let ne = self.successor(ne, second_j);
+            let sw = self.successor(sw, second_j);
+            let se = self.successor(se, second_j);
+            self.join(nw, ne, sw, se)
+        };
+
+        if self.leap_abandoned {
+            return DEAD;
+        }
+        self.memo.insert((id, j), result);
+        result
+    }
+
+    /// Brute force evaluation of the centre 2x2 square of a 4x4 node after one
+    /// generation.
+    fn successor_base(&mut self, id: NodeId) -> NodeId {
+        let mut cells = [[false; 4]; 4];
+        for (y, row) in cells.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                *cell = self.get_in(id, x as i64, y as i64) == ALIVE;
+            }
+        }
+
+        let mut next = [DEAD; 4];
+        for (i, (x, y)) in [(1, 1), (2, 1), (1, 2), (2, 2)].into_iter().enumerate() {
+            let alive_neighbors = (y - 1..=y + 1)
+                .flat_map(|ny| (x - 1..=x + 1).map(move |nx| (nx, ny)))
+                .filter(|&(nx, ny)| (nx, ny) != (x, y) && cells[ny][nx])
+                .count() as u8;
+            let cell = if cells[y][x] { Cell::Alive } else { Cell::Dead };
+            if let Cell::Alive = cell.next(alive_neighbors, &self.rule) {
+                next[i] = ALIVE;
+            }
+        }
+        self.join(next[0], next[1], next[2], next[3])
+    }
+
+    /// Returns the leaf at (x,y), relative to the node top-left cell.
+    fn get_in(&self, mut id: NodeId, mut x: i64, mut y: i64) -> NodeId {
+        loop {
+            let node = self.nodes[id as usize];
+            if node.level == 0 {
+                return id;
+            }
+            let half = 1i64 << (node.level - 1);
+            id = match (x >= half, y >= half) {
+                (false, false) => node.nw,
+                (true, false) => node.ne,
+                (false, true) => node.sw,
+                (true, true) => node.se,
+            };
+            x %= half;
+            y %= half;
+        }
+    }
+
+    /// Returns a copy of the node with the leaf at (x,y), relative to the node
+    /// top-left cell, replaced.
+    fn set_in(&mut self, id: NodeId, x: i64, y: i64, leaf: NodeId) -> NodeId {
+        let node = self.nodes[id as usize];
+        if node.level == 0 {
+            return leaf;
+        }
+        let half = 1i64 << (node.level - 1);
+        let [mut nw, mut ne, mut sw, mut se] = self.quadrants(id);
+        let quadrant = match (x >= half, y >= half) {
+            (false, false) => &mut nw,
+            (true, false) => &mut ne,
+            (false, true) => &mut sw,
+            (true, true) => &mut se,
+        };
+        *quadrant = self.set_in(*quadrant, x % half, y % half, leaf);
+        self.join(nw, ne, sw, se)
+    }
+
+    /// Returns `true` when the root contains the (x,y) coordinates.
+    fn contains(&self, x: i64, y: i64) -> bool {
+        let size = 1i64 << self.nodes[self.root as usize].level;
+        (self.origin_x..self.origin_x + size).contains(&x)
+            && (self.origin_y..self.origin_y + size).contains(&y)
+    }
+
+    /// Collects the living cells of a node whose top-left cell is at (x,y).
+    /// Cells beyond the range of `Coords`, reached after long leaps, are left
+    /// out.
+    fn collect_live_cells(&self, id: NodeId, x: i64, y: i64, cells: &mut Vec<(Coords, Cell)>) {
+        let node = self.nodes[id as usize];
+        let size = 1i64 << node.level;
+        let in_range = |start: i64| start <= i32::MAX as i64 && start + size > i32::MIN as i64;
+        if node.population == 0 || !in_range(x) || !in_range(y) {
+            return;
+        }
+        if node.level == 0 {
+            if let (Ok(x), Ok(y)) = (i32::try_from(x), i32::try_from(y)) {
+                cells.push((Coords { x, y }, Cell::Alive));
+            }
+            return;
+        }
+        let half = 1i64 << (node.level - 1);
+        self.collect_live_cells(node.nw, x, y, cells);
+        self.collect_live_cells(node.ne, x + half, y, cells);
+        self.collect_live_cells(node.sw, x, y + half, cells);
+        self.collect_live_cells(node.se, x + half, y + half, cells);
+    }
+
+    /// Drops every node that is not reachable from the root.
+    ///
+    /// Reachable nodes are copied into a new arena, children before parents,
+    /// and memoized results survive only when both ends are still reachable.
+    fn collect_garbage(&mut self) {
+        let mut remap: HashMap<NodeId, NodeId> = HashMap::from([(DEAD, DEAD), (ALIVE, ALIVE)]);
+        let mut nodes = vec![self.nodes[DEAD as usize], self.nodes[ALIVE as usize]];
+        let mut stack = vec![(self.root, false)];
+
+        while let Some((id, children_done)) = stack.pop() {
+            if remap.contains_key(&id) {
+                continue;
+            }
+            if children_done {
+                let mut node = self.nodes[id as usize];
+                node.nw = remap[&node.nw];
+                node.ne = remap[&node.ne];
+                node.sw = remap[&node.sw];
+                node.se = remap[&node.se];
+                remap.insert(id, nodes.len() as NodeId);
+                nodes.push(node);
+            } else {
+                stack.push((id, true));
+                stack.extend(self.quadrants(id).map(|q| (q, false)));
+            }
+        }
+
+        self.canonical = nodes
+            .iter()
+            .enumerate()
+            .skip(2)
+            .map(|(id, node)| ([node.nw, node.ne, node.sw, node.se], id as NodeId))
+            .collect();
+        self.memo = self
+            .memo
+            .iter()
+            .filter_map(|(&(id, j), result)| Some(((*remap.get(&id)?, j), *remap.get(result)?)))
+            .collect();
+        self.root = remap[&self.root];
+        self.nodes = nodes;
+    }
+}
+
+impl Simulation for HashLife {
+    fn step(&mut self) {
+        self.step_pow2(0);
+    }
+
+    fn get_cell(&self, x: i32, y: i32) -> Cell {
+        let (x, y) = (x as i64, y as i64);
+        if !self.contains(x, y) {
+            return Cell::Dead;
+        }
+        match self.get_in(self.root, x - self.origin_x, y - self.origin_y) {
+            ALIVE => Cell::Alive,
+            _ => Cell::Dead,
+        }
+    }
+
+    /// Dying cells are not supported and are set as dead.
+    fn set_cell(&mut self, x: i32, y: i32, value: Cell) {
+        let (x, y) = (x as i64, y as i64);
+        while !self.contains(x, y) {
+            self.expand();
+        }
+        let leaf = if let Cell::Alive = value { ALIVE } else { DEAD };
+        self.root = self.set_in(self.root, x - self.origin_x, y - self.origin_y, leaf);
+    }
+
+    fn clear(&mut self) {
+        let level = self.nodes[self.root as usize].level;
+        self.root = self.empty(level);
+    }
+
+    fn live_cells(&self) -> Box<dyn Iterator<Item = (Coords, Cell)> + '_> {
+        let mut cells = Vec::new();
+        self.collect_live_cells(self.root, self.origin_x, self.origin_y, &mut cells);
+        Box::new(cells.into_iter())
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+
+    fn rule(&self) -> &Rule {
+        &self.rule
+    }
+
+    fn population(&self) -> usize {
+        self.nodes[self.root as usize].population as usize
+    }
+}
+
+impl fmt::Display for HashLifeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HashLifeError::UnsupportedRule => write!(
+                f,
+                "hashlife supports neither Generations rules nor birth on 0 neighbors"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HashLifeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Bounds, SparseState};
+
+    fn glider<S: Simulation>(mut sim: S) -> S {
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            sim.set_cell(x, y, Cell::Alive);
+        }
+        sim
+    }
+
+    fn sorted_cells<S: Simulation>(sim: &S) -> Vec<(i32, i32)> {
+        let mut cells: Vec<_> = sim.live_cells().map(|(c, _)| (c.x, c.y)).collect();
+        cells.sort();
+        cells
+    }
+
+    #[test]
+    fn agrees_with_sparse_engine_one_step_at_a_time() {
+        // R-pentomino, a chaotic methuselah
+        let mut hashlife = HashLife::new(Rule::default()).unwrap();
+        let mut sparse = SparseState::new(Rule::default());
+        for (x, y) in [(1, 0), (2, 0), (0, 1), (1, 1), (1, 2)] {
+            hashlife.set_cell(x, y, Cell::Alive);
+            sparse.set_cell(x, y, Cell::Alive);
+        }
+
+        for _ in 0..100 {
+            hashlife.step();
+            sparse.step();
+            assert_eq!(sorted_cells(&hashlife), sorted_cells(&sparse));
+        }
+    }
+
+    #[test]
+    fn leaps_power_of_two_generations() {
+        let mut hashlife = HashLife::new(Rule::default()).unwrap();
+        for (x, y) in [(1, 0), (2, 0), (0, 1), (1, 1), (1, 2)] {
+            hashlife.set_cell(x, y, Cell::Alive);
+        }
+        let mut sparse = SparseState::new(Rule::default());
+        for (x, y) in [(1, 0), (2, 0), (0, 1), (1, 1), (1, 2)] {
+            sparse.set_cell(x, y, Cell::Alive);
+        }
+
+        hashlife.step_pow2(6);
+        hashlife.step_pow2(5);
+        for _ in 0..96 {
+            sparse.step();
+        }
+        assert_eq!(hashlife.generation(), 96);
+        assert_eq!(sorted_cells(&hashlife), sorted_cells(&sparse));
+    }
+
+    #[test]
+    fn moves_a_glider_far_away() {
+        let mut hashlife = glider(HashLife::new(Rule::default()).unwrap());
+
+        // a glider travels one cell diagonally every 4 generations
+        hashlife.step_pow2(30);
+        let distance = (1 << 30) / 4;
+        assert_eq!(hashlife.population(), 5);
+        assert_eq!(
+            hashlife.bounding_box(),
+            Some(Bounds {
+                x_min: distance,
+                x_max: distance + 2,
+                y_min: distance,
+                y_max: distance + 2,
+            })
+        );
+    }
+
+    #[test]
+    fn collects_garbage_over_the_cap() {
+        let mut hashlife = glider(HashLife::with_max_nodes(Rule::default(), 64).unwrap());
+        let mut sparse = glider(SparseState::new(Rule::default()));
+
+        for _ in 0..20 {
+            hashlife.step_pow2(2);
+            assert!(hashlife.node_count() <= 64);
+        }
+        for _ in 0..80 {
+            sparse.step();
+        }
+        assert_eq!(sorted_cells(&hashlife), sorted_cells(&sparse));
+    }
+
+    #[test]
+    fn splits_leaps_over_the_cap() {
+        let mut hashlife = glider(HashLife::with_max_nodes(Rule::default(), 64).unwrap());
+        let mut sparse = glider(SparseState::new(Rule::default()));
+
+        hashlife.step_pow2(8);
+        for _ in 0..256 {
+            sparse.step();
+        }
+        assert_eq!(hashlife.generation(), 256);
+        assert_eq!(sorted_cells(&hashlife), sorted_cells(&sparse));
+    }
+
+    #[test]
+    fn leaves_out_cells_beyond_coords_range() {
+        let mut hashlife = glider(HashLife::new(Rule::default()).unwrap());
+        hashlife.step_pow2(36);
+        assert_eq!(hashlife.population(), 5);
+        assert_eq!(hashlife.live_cells().count(), 0);
+    }
+
+    #[test]
+    fn converts_to_and_from_state() {
+        let config = Config {
+            cols: 16,
+            rows: 16,
+            ..Config::default()
+        };
+        let state = glider(State::new(&config));
+        let mut hashlife = HashLife::from_simulation(&state).unwrap();
+        hashlife.step_pow2(2);
+
+        let mut expected = glider(State::new(&config));
+        for _ in 0..4 {
+            expected.step();
+        }
+        let state = hashlife.to_state(&config);
+        assert_eq!(state.generation(), 4);
+        assert_eq!(sorted_cells(&state), sorted_cells(&expected));
+    }
+
+    #[test]
+    fn rejects_generations_rules() {
+        let rule = "B2/S/C3".parse().unwrap();
+        assert_eq!(
+            HashLife::new(rule).err(),
+            Some(HashLifeError::UnsupportedRule)
+        );
+    }
+}
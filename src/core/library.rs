@@ -0,0 +1,128 @@
+//! Catalogue of classic Conway's Life patterns, embedded in the crate.
+
+use super::format::{Pattern, rle};
+
+// Name, description and RLE of each pattern.
+#[rustfmt::skip]
+const PATTERNS: &[(&str, &str, &str)] = &[
+    ("glider",         "c/4 diagonal spaceship",
+     "x = 3, y = 3\nbo$2bo$3o!"),
+    ("lwss",           "lightweight spaceship, c/2 orthogonal",
+     "x = 5, y = 4\nbo2bo$o4b$o3bo$4o!"),
+    ("mwss",           "middleweight spaceship, c/2 orthogonal",
+     "x = 6, y = 5\n3bo2b$bo3bo$o5b$o4bo$5o!"),
+    ("hwss",           "heavyweight spaceship, c/2 orthogonal",
+     "x = 7, y = 5\n3b2o2b$bo4bo$o6b$o5bo$6o!"),
+    ("block",          "still life",
+     "x = 2, y = 2\n2o$2o!"),
+    ("beehive",        "still life",
+     "x = 4, y = 3\nb2ob$o2bo$b2ob!"),
+    ("loaf",           "still life",
+     "x = 4, y = 4\nb2ob$o2bo$bobo$2bob!"),
+    ("boat",           "still life",
+     "x = 3, y = 3\n2ob$obo$bob!"),
+    ("ship",           "still life",
+     "x = 3, y = 3\n2ob$obo$b2o!"),
+    ("tub",            "still life",
+     "x = 3, y = 3\nbob$obo$bob!"),
+    ("pond",           "still life",
+     "x = 4, y = 4\nb2ob$o2bo$o2bo$b2ob!"),
+    ("blinker",        "period 2 oscillator",
+     "x = 3, y = 1\n3o!"),
+    ("toad",           "period 2 oscillator",
+     "x = 4, y = 2\nb3o$3o!"),
+    ("beacon",         "period 2 oscillator",
+     "x = 4, y = 4\n2o2b$2o2b$2b2o$2b2o!"),
+    ("pulsar",         "period 3 oscillator",
+     "x = 13, y = 13\n2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$\
+      o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!"),
+    ("pentadecathlon", "period 15 oscillator",
+     "x = 10, y = 3\n2bo4bo2b$2ob4ob2o$2bo4bo!"),
+    ("gosper-gun",     "Gosper glider gun, a glider every 30 generations",
+     "x = 36, y = 9\n24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$\
+      2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!"),
+    ("r-pentomino",    "methuselah, stabilises after 1103 generations",
+     "x = 3, y = 3\nb2o$2o$bo!"),
+    ("acorn",          "methuselah, stabilises after 5206 generations",
+     "x = 7, y = 3\nbo5b$3bo3b$2o2b3o!"),
+    ("diehard",        "methuselah, dies after 130 generations",
+     "x = 8, y = 3\n6bob$2o6b$bo3b3o!"),
+];
+
+/// Iterates over the names and descriptions of the patterns.
+pub fn list() -> impl Iterator<Item = (&'static str, &'static str)> {
+    PATTERNS
+        .iter()
+        .map(|&(name, description, _)| (name, description))
+}
+
+/// Returns a pattern by name, ignoring case.
+pub fn get(name: &str) -> Option<Pattern> {
+    let &(name, _, input) = PATTERNS
+        .iter()
+        .find(|(pattern, ..)| pattern.eq_ignore_ascii_case(name))?;
+    let pattern = rle::parse(input).expect("library patterns are valid RLE");
+    Some(Pattern {
+        name: Some(name.to_string()),
+        ..pattern
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        Cell, Coords, CycleDetector, Simulation, SparseState, census::Census, test_support,
+    };
+
+    fn sparse(name: &str) -> SparseState {
+        test_support::sparse_pattern(&get(name).unwrap())
+    }
+
+    #[test]
+    fn every_pattern_is_valid() {
+        for (name, _) in list() {
+            let pattern = get(name).unwrap();
+            assert_eq!(pattern.name.as_deref(), Some(name));
+            assert!(pattern.cells.iter().all(|(Coords { x, y }, cell)| {
+                *cell == Cell::Alive && (*x as u32) < pattern.width && (*y as u32) < pattern.height
+            }));
+        }
+        assert!(get("GLIDER").is_some());
+        assert!(get("unknown").is_none());
+    }
+
+    #[test]
+    fn patterns_behave_as_described() {
+        let census = Census::new();
+        for name in ["glider", "lwss", "pulsar"] {
+            let cells: Vec<_> = sparse(name).live_cells().map(|(c, _)| c).collect();
+            assert_eq!(census.identify(&cells), Some(name));
+        }
+
+        let cycle =
+            |name, generations| CycleDetector::default().run(&mut sparse(name), generations);
+        assert_eq!(cycle("pentadecathlon", 20).map(|c| c.period), Some(15));
+        assert_eq!(
+            cycle("mwss", 10).and_then(|c| c.speed()).as_deref(),
+            Some("c/2 orthogonal")
+        );
+        assert_eq!(
+            cycle("hwss", 10).and_then(|c| c.speed()).as_deref(),
+            Some("c/2 orthogonal")
+        );
+
+        let mut diehard = sparse("diehard");
+        for _ in 0..130 {
+            diehard.step();
+        }
+        assert_eq!(diehard.population(), 0);
+
+        let mut gun = sparse("gosper-gun");
+        assert_eq!(gun.population(), 36);
+        for _ in 0..30 {
+            gun.step();
+        }
+        assert_eq!(gun.population(), 36 + 5);
+    }
+}
@@ -0,0 +1,55 @@
+/// SplitMix64 pseudo-random number generator.
+///
+/// Small and fast, and above all deterministic: the same seed yields the same
+/// sequence on every machine, so that random soups can be reproduced from
+/// their seed alone.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number uniformly distributed in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        // the 53 most significant bits fill the mantissa exactly
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns true with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_reference_sequence() {
+        let mut rng = Rng::new(0);
+        assert_eq!(rng.next_u64(), 0xe220a8397b1dcdaf);
+        assert_eq!(rng.next_u64(), 0x6e789e6aa1b965f4);
+    }
+
+    #[test]
+    fn chance_follows_probability() {
+        let mut rng = Rng::new(42);
+        let hits = (0..10_000).filter(|_| rng.chance(0.3)).count();
+        assert!((2_800..3_200).contains(&hits), "{hits}");
+        assert!(!rng.chance(0.0));
+        assert!(rng.chance(1.0));
+    }
+}
@@ -0,0 +1,251 @@
+//! Life-like cellular automata share Conway's square grid and 8-cell
+//! neighborhood, and differ only in the neighbor counts that make a dead cell
+//! come alive (birth) or keep a living cell alive (survival).
+//!
+//! Rules are usually written in B/S notation, eg. Conway's Game of Life is
+//! `B3/S23`, HighLife is `B36/S23` and Seeds is `B2/S`. The older S/B notation
+//! lists survival counts first and birth counts after, without prefixes, eg.
+//! `23/3`.
+//!
+//! Generations rules add a third part with the number of cell states, eg.
+//! `B2/S345/C4` or `345/2/4`. A living cell that does not survive goes through
+//! `states - 2` dying states before becoming dead. Dying cells do not count as
+//! living neighbors and cannot give birth. Brian's Brain is `B2/S/C3`.
+
+use std::{fmt, str::FromStr};
+
+/// Birth and survival conditions, stored as bitmasks where the n-th bit is set
+/// when a cell with `n` living neighbors is born/survives, and number of cell
+/// states (2 for Life-like rules).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Rule {
+    birth: u16,
+    survival: u16,
+    states: u8,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum ParseRuleError {
+    // The rule is not made of two `/` separated parts, the birth and survival
+    // counts, optionally followed by a third one, the number of states.
+    InvalidFormat,
+    // A neighbor count is not a digit in range `0..=8`.
+    InvalidNeighborCount(char),
+    // The number of states is not a number in range `2..=255`.
+    InvalidStates,
+}
+
+impl Rule {
+    /// Builds a rule from the lists of neighbor counts leading to birth and
+    /// survival. Counts greater than 8 are ignored.
+    pub fn new(birth: &[u8], survival: &[u8]) -> Self {
+        Rule {
+            birth: to_mask(birth),
+            survival: to_mask(survival),
+            states: 2,
+        }
+    }
+
+    /// Turns the rule into a Generations rule with the given number of states.
+    /// `states` is clamped to a minimum of 2, the Life-like case.
+    pub fn with_states(self, states: u8) -> Self {
+        Rule {
+            states: states.max(2),
+            ..self
+        }
+    }
+
+    /// Returns the number of cell states, including dead and alive.
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// Returns `true` when a dead cell with `alive_neighbors` comes alive.
+    pub fn is_birth(&self, alive_neighbors: u8) -> bool {
+        self.birth & (1 << alive_neighbors) != 0
+    }
+
+    /// Returns `true` when a living cell with `alive_neighbors` stays alive.
+    pub fn is_survival(&self, alive_neighbors: u8) -> bool {
+        self.survival & (1 << alive_neighbors) != 0
+    }
+}
+
+/// Conway's Game of Life, `B3/S23`.
+impl Default for Rule {
+    fn default() -> Self {
+        Rule::new(&[3], &[2, 3])
+    }
+}
+
+impl FromStr for Rule {
+    type Err = ParseRuleError;
+
+    /// Parses both B/S (`B36/S23`) and S/B (`23/36`) notations, optionally
+    /// followed by the number of states (`/C3` or `/3`). Prefixes are case
+    /// insensitive, and in B/S notation the two parts can be swapped.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split('/');
+        let (Some(left), Some(right)) = (parts.next(), parts.next()) else {
+            return Err(ParseRuleError::InvalidFormat);
+        };
+        let states = match (parts.next(), parts.next()) {
+            (None, _) => 2,
+            (Some(states), None) => parse_states(states)?,
+            _ => return Err(ParseRuleError::InvalidFormat),
+        };
+
+        let prefix = |part: &str| part.chars().next().map(|c| c.to_ascii_uppercase());
+        let (birth, survival) = match (prefix(left), prefix(right)) {
+            (Some('B'), Some('S')) => (&left[1..], &right[1..]),
+            (Some('S'), Some('B')) => (&right[1..], &left[1..]),
+            (Some('B' | 'S'), _) | (_, Some('B' | 'S')) => {
+                return Err(ParseRuleError::InvalidFormat);
+            }
+            _ => (right, left),
+        };
+
+        Ok(Rule {
+            birth: parse_counts(birth)?,
+            survival: parse_counts(survival)?,
+            states,
+        })
+    }
+}
+
+impl fmt::Display for Rule {
+    /// Formats the rule in B/S notation, eg. `B3/S23` or `B2/S345/C4`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = |mask: u16| -> String {
+            (0..=8)
+                .filter(|n| mask & (1 << n) != 0)
+                .map(|n| char::from(b'0' + n))
+                .collect()
+        };
+        write!(f, "B{}/S{}", counts(self.birth), counts(self.survival))?;
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ParseRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseRuleError::InvalidFormat => write!(f, "rule must look like B3/S23 or 23/3"),
+            ParseRuleError::InvalidNeighborCount(c) => {
+                write!(f, "invalid neighbor count '{c}', expected 0-8")
+            }
+            ParseRuleError::InvalidStates => write!(f, "number of states must be in 2-255"),
+        }
+    }
+}
+
+impl std::error::Error for ParseRuleError {}
+
+fn to_mask(counts: &[u8]) -> u16 {
+    counts
+        .iter()
+        .filter(|&&n| n <= 8)
+        .fold(0, |mask, &n| mask | (1 << n))
+}
+
+fn parse_counts(counts: &str) -> Result<u16, ParseRuleError> {
+    counts.chars().try_fold(0, |mask, c| match c.to_digit(10) {
+        Some(n) if n <= 8 => Ok(mask | (1 << n)),
+        _ => Err(ParseRuleError::InvalidNeighborCount(c)),
+    })
+}
+
+fn parse_states(states: &str) -> Result<u8, ParseRuleError> {
+    let states = states
+        .strip_prefix(['C', 'c'])
+        .unwrap_or(states)
+        .parse::<u8>()
+        .map_err(|_| ParseRuleError::InvalidStates)?;
+    if states < 2 {
+        return Err(ParseRuleError::InvalidStates);
+    }
+    Ok(states)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bs_notation() {
+        let rule: Rule = "B36/S23".parse().unwrap();
+        assert_eq!(rule, Rule::new(&[3, 6], &[2, 3]));
+
+        let rule: Rule = "b2/s".parse().unwrap();
+        assert_eq!(rule, Rule::new(&[2], &[]));
+
+        let rule: Rule = "S23/B3".parse().unwrap();
+        assert_eq!(rule, Rule::default());
+    }
+
+    #[test]
+    fn parses_sb_notation() {
+        let rule: Rule = "23/3".parse().unwrap();
+        assert_eq!(rule, Rule::default());
+
+        let rule: Rule = "/2".parse().unwrap();
+        assert_eq!(rule, Rule::new(&[2], &[]));
+    }
+
+    #[test]
+    fn parses_generations_notation() {
+        let rule: Rule = "B2/S345/C4".parse().unwrap();
+        assert_eq!(rule, Rule::new(&[2], &[3, 4, 5]).with_states(4));
+
+        let rule: Rule = "345/2/4".parse().unwrap();
+        assert_eq!(rule, Rule::new(&[2], &[3, 4, 5]).with_states(4));
+
+        let rule: Rule = "B3/S23/C2".parse().unwrap();
+        assert_eq!(rule, Rule::default());
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        assert_eq!("B3S23".parse::<Rule>(), Err(ParseRuleError::InvalidFormat));
+        assert_eq!("B3/23".parse::<Rule>(), Err(ParseRuleError::InvalidFormat));
+        assert_eq!(
+            "B39/S23".parse::<Rule>(),
+            Err(ParseRuleError::InvalidNeighborCount('9'))
+        );
+        assert_eq!(
+            "2x/3".parse::<Rule>(),
+            Err(ParseRuleError::InvalidNeighborCount('x'))
+        );
+        assert_eq!(
+            "B2/S/C1".parse::<Rule>(),
+            Err(ParseRuleError::InvalidStates)
+        );
+        assert_eq!(
+            "B2/S/Cx".parse::<Rule>(),
+            Err(ParseRuleError::InvalidStates)
+        );
+        assert_eq!(
+            "B2/S/C3/4".parse::<Rule>(),
+            Err(ParseRuleError::InvalidFormat)
+        );
+    }
+
+    #[test]
+    fn formats_bs_notation() {
+        assert_eq!(Rule::default().to_string(), "B3/S23");
+        assert_eq!("23/36".parse::<Rule>().unwrap().to_string(), "B36/S23");
+        assert_eq!("/2/3".parse::<Rule>().unwrap().to_string(), "B2/S/C3");
+    }
+
+    #[test]
+    fn evaluates_birth_and_survival() {
+        let rule = Rule::default();
+        assert!(rule.is_birth(3));
+        assert!(!rule.is_birth(2));
+        assert!(rule.is_survival(2));
+        assert!(!rule.is_survival(4));
+    }
+}
@@ -0,0 +1,149 @@
+//! Common interface of the simulation engines.
+//!
+//! The dense `State` and `BitState` are finite boards whose edges follow a
+//! `Topology`, while `SparseState` and `HashLife` are unbounded planes that
+//! only store non-dead cells.
+//! Renderers and games rely on this trait only, so they can drive any engine.
+
+use super::{
+    bitboard::BitState,
+    bounds::Bounds,
+    cell::Cell,
+    config::{Backend, Config},
+    coords::Coords,
+    rule::Rule,
+    state::State,
+    stats::Stats,
+};
+
+pub trait Simulation {
+    /// Compute next generation state based on current one.
+    fn step(&mut self);
+
+    /// Returns the cell at (x,y), coordinates falling off the board are dead.
+    fn get_cell(&self, x: i32, y: i32) -> Cell;
+
+    /// Updates the cell at (x,y). Coordinates falling off the board are
+    /// ignored.
+    fn set_cell(&mut self, x: i32, y: i32, value: Cell);
+
+    /// Flips cell state.
+    fn toggle_cell(&mut self, coords: &Coords) {
+        let mut cell = self.get_cell(coords.x, coords.y);
+        cell.toggle();
+        self.set_cell(coords.x, coords.y, cell);
+    }
+
+    /// Sets every cell to dead.
+    fn clear(&mut self);
+
+    /// Returns an iterator over cells that are not dead, either alive or dying.
+    /// Ordering depends on the engine and is never guaranteed.
+    fn live_cells(&self) -> Box<dyn Iterator<Item = (Coords, Cell)> + '_>;
+
+    fn generation(&self) -> u64;
+
+    /// Overrides the generation counter, eg. when restoring cells computed by
+    /// another engine.
+    fn set_generation(&mut self, generation: u64);
+
+    fn rule(&self) -> &Rule;
+
+    /// Returns the bounds of the non-dead cells, or `None` when every cell is
+    /// dead.
+    fn bounding_box(&self) -> Option<Bounds> {
+        Bounds::from_coords(self.live_cells().map(|(coords, _)| coords))
+    }
+
+    /// Returns the number of living cells, dying cells are not counted.
+    fn population(&self) -> usize {
+        self.live_cells()
+            .filter(|(_, cell)| matches!(cell, Cell::Alive))
+            .count()
+    }
+
+    /// Maps coordinates onto the board, returning `None` when they fall off
+    /// it. Unbounded engines keep coordinates as they are.
+    fn wrap_coords(&self, coords: &Coords) -> Option<Coords> {
+        Some(*coords)
+    }
+
+    /// Returns the cells that changed in the last generation, and the cells
+    /// edited since then, so that renderers can redraw only those. Returns
+    /// `None` when the engine does not track changes.
+    fn changed_cells(&self) -> Option<Box<dyn Iterator<Item = Coords> + '_>> {
+        None
+    }
+
+    /// Returns the statistics of the latest generations, or `None` when the
+    /// engine does not track them.
+    fn stats(&self) -> Option<&Stats> {
+        None
+    }
+}
+
+impl<S: Simulation + ?Sized> Simulation for Box<S> {
+    fn step(&mut self) {
+        (**self).step()
+    }
+
+    fn get_cell(&self, x: i32, y: i32) -> Cell {
+        (**self).get_cell(x, y)
+    }
+
+    fn set_cell(&mut self, x: i32, y: i32, value: Cell) {
+        (**self).set_cell(x, y, value)
+    }
+
+    fn toggle_cell(&mut self, coords: &Coords) {
+        (**self).toggle_cell(coords)
+    }
+
+    fn clear(&mut self) {
+        (**self).clear()
+    }
+
+    fn live_cells(&self) -> Box<dyn Iterator<Item = (Coords, Cell)> + '_> {
+        (**self).live_cells()
+    }
+
+    fn generation(&self) -> u64 {
+        (**self).generation()
+    }
+
+    fn set_generation(&mut self, generation: u64) {
+        (**self).set_generation(generation)
+    }
+
+    fn rule(&self) -> &Rule {
+        (**self).rule()
+    }
+
+    fn bounding_box(&self) -> Option<Bounds> {
+        (**self).bounding_box()
+    }
+
+    fn population(&self) -> usize {
+        (**self).population()
+    }
+
+    fn wrap_coords(&self, coords: &Coords) -> Option<Coords> {
+        (**self).wrap_coords(coords)
+    }
+
+    fn changed_cells(&self) -> Option<Box<dyn Iterator<Item = Coords> + '_>> {
+        (**self).changed_cells()
+    }
+
+    fn stats(&self) -> Option<&Stats> {
+        (**self).stats()
+    }
+}
+
+/// Creates the dense engine selected by the configured backend.
+pub fn build_simulation(config: &Config) -> Box<dyn Simulation> {
+    match config.backend {
+        Backend::Bits if config.rule.states() == 2 => Box::new(BitState::new(config)),
+        Backend::Bits | Backend::Cells => Box::new(State::new(config)),
+    }
+}
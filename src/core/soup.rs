@@ -0,0 +1,223 @@
+//! Reproducible random soups.
+
+use std::{fmt, str::FromStr};
+
+use super::{bounds::Bounds, coords::Coords, rng::Rng};
+
+/// Symmetry of a random soup, around the centre of its area.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Symmetry {
+    #[default]
+    None,
+    // Invariant under a half turn.
+    C2,
+    // Invariant under a quarter turn.
+    C4,
+    // Invariant under quarter turns and reflections.
+    D8,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ParseSymmetryError;
+
+/// Random soup description, the same soup always yields the same cells.
+#[derive(Clone, Debug)]
+pub struct Soup {
+    pub seed: u64,
+    // Probability of a cell being alive, from 0 to 1.
+    pub density: f64,
+    // Area to fill, the whole board when not set.
+    pub area: Option<Bounds>,
+    pub symmetry: Symmetry,
+}
+
+impl Soup {
+    /// Returns a soup filling the whole board at 50% density.
+    pub fn new(seed: u64) -> Self {
+        Soup {
+            seed,
+            density: 0.5,
+            area: None,
+            symmetry: Symmetry::None,
+        }
+    }
+
+    /// Returns the area filled on a `cols x rows` board. C4 and D8 symmetries
+    /// need a square, which is centred in the area.
+    pub fn area(&self, cols: u32, rows: u32) -> Bounds {
+        let area = self.area.unwrap_or(Bounds {
+            x_min: 0,
+            x_max: cols as i32 - 1,
+            y_min: 0,
+            y_max: rows as i32 - 1,
+        });
+        if matches!(self.symmetry, Symmetry::None | Symmetry::C2) {
+            return area;
+        }
+
+        let side = area.width().min(area.height()) as i32;
+        let x_min = area.x_min + (area.width() as i32 - side) / 2;
+        let y_min = area.y_min + (area.height() as i32 - side) / 2;
+        Bounds {
+            x_min,
+            x_max: x_min + side - 1,
+            y_min,
+            y_max: y_min + side - 1,
+        }
+    }
+
+    /// Returns the living cells of the soup on a `cols x rows` board.
+    ///
+    /// Cells are visited row by row, and a random draw decides each orbit of
+    /// the symmetry group on its first cell, so that the sequence of draws
+    /// only depends on the seed and the area size.
+    pub fn cells(&self, cols: u32, rows: u32) -> Vec<Coords> {
+        let area = self.area(cols, rows);
+        let (w, h) = (area.width() as i32, area.height() as i32);
+        let mut rng = Rng::new(self.seed);
+        let mut cells = Vec::new();
+
+        for y in 0..h {
+            for x in 0..w {
+                let orbit = self.orbit(x, y, w, h);
+                let is_first = orbit.iter().all(|&(ox, oy)| (y, x) <= (oy, ox));
+                if is_first && rng.chance(self.density) {
+                    cells.extend(orbit.into_iter().map(|(ox, oy)| Coords {
+                        x: area.x_min + ox,
+                        y: area.y_min + oy,
+                    }));
+                }
+            }
+        }
+
+        cells.sort_by_key(|coords| (coords.y, coords.x));
+        cells.dedup();
+        cells
+    }
+
+    /// Returns the images of (x,y) in a `w x h` area under the symmetry.
+    fn orbit(&self, x: i32, y: i32, w: i32, h: i32) -> Vec<(i32, i32)> {
+        let (rx, ry) = (w - 1 - x, h - 1 - y);
+        match self.symmetry {
+            Symmetry::None => vec![(x, y)],
+            Symmetry::C2 => vec![(x, y), (rx, ry)],
+            // w == h for the square symmetries
+            Symmetry::C4 => vec![(x, y), (ry, x), (rx, ry), (y, rx)],
+            Symmetry::D8 => vec![
+                (x, y),
+                (ry, x),
+                (rx, ry),
+                (y, rx),
+                (rx, y),
+                (x, ry),
+                (y, x),
+                (ry, rx),
+            ],
+        }
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = ParseSymmetryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "NONE" | "C1" => Ok(Symmetry::None),
+            "C2" => Ok(Symmetry::C2),
+            "C4" => Ok(Symmetry::C4),
+            "D8" => Ok(Symmetry::D8),
+            _ => Err(ParseSymmetryError),
+        }
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Symmetry::None => "C1",
+            Symmetry::C2 => "C2",
+            Symmetry::C4 => "C4",
+            Symmetry::D8 => "D8",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl fmt::Display for ParseSymmetryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "symmetry must be one of C1, C2, C4 or D8")
+    }
+}
+
+impl std::error::Error for ParseSymmetryError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_invariant(cells: &[Coords], map: impl Fn(i32, i32) -> (i32, i32)) -> bool {
+        cells.iter().all(|c| {
+            let (x, y) = map(c.x, c.y);
+            cells.contains(&Coords { x, y })
+        })
+    }
+
+    #[test]
+    fn same_seed_same_soup() {
+        let soup = Soup {
+            density: 0.3,
+            ..Soup::new(1234)
+        };
+        assert_eq!(soup.cells(40, 30), soup.cells(40, 30));
+        assert_ne!(soup.cells(40, 30), Soup::new(1235).cells(40, 30));
+
+        let count = soup.cells(40, 30).len();
+        assert!((300..420).contains(&count), "{count}");
+    }
+
+    #[test]
+    fn fills_the_area_only() {
+        let area = Bounds {
+            x_min: 5,
+            x_max: 14,
+            y_min: 2,
+            y_max: 6,
+        };
+        let soup = Soup {
+            density: 1.0,
+            area: Some(area),
+            ..Soup::new(0)
+        };
+        let cells = soup.cells(40, 30);
+        assert_eq!(cells.len(), 50);
+        assert!(cells.iter().all(|coords| area.contains(coords)));
+    }
+
+    #[test]
+    fn soups_are_symmetric() {
+        let soup = |symmetry| Soup {
+            symmetry,
+            ..Soup::new(99)
+        };
+
+        // 20x11 board, rotations around (9.5, 5)
+        let cells = soup(Symmetry::C2).cells(20, 11);
+        assert!(is_invariant(&cells, |x, y| (19 - x, 10 - y)));
+
+        // 11x11 square centred in the board, from x=4 to x=14
+        let cells = soup(Symmetry::C4).cells(20, 11);
+        assert!(cells.iter().all(|c| (4..=14).contains(&c.x)));
+        assert!(is_invariant(&cells, |x, y| (14 - y, x - 4)));
+
+        let cells = soup(Symmetry::D8).cells(20, 11);
+        assert!(is_invariant(&cells, |x, y| (y + 4, x - 4)));
+        assert!(is_invariant(&cells, |x, y| (18 - x, y)));
+    }
+
+    #[test]
+    fn parses_symmetry() {
+        assert_eq!("c4".parse(), Ok(Symmetry::C4));
+        assert_eq!("C1".parse(), Ok(Symmetry::None));
+        assert_eq!("D4".parse::<Symmetry>(), Err(ParseSymmetryError));
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use super::{cell::Cell, coords::Coords, rule::Rule, simulation::Simulation};
+
+/// Unbounded Game of Life state
+///
+/// Only non-dead cells are stored, keyed by their coordinates, so patterns can
+/// grow in every direction without ever hitting a board edge, and memory and
+/// computation are proportional to the population rather than to the area.
+pub struct SparseState {
+    generation: u64,
+    rule: Rule,
+    cells: HashMap<Coords, Cell>,
+}
+
+impl SparseState {
+    pub fn new(rule: Rule) -> Self {
+        SparseState {
+            generation: 0,
+            rule,
+            cells: HashMap::new(),
+        }
+    }
+
+    /// Counts the living neighbors of every cell that has at least one, which
+    /// are the only dead cells that can possibly be born.
+    fn count_alive_neighbors(&self) -> HashMap<Coords, u8> {
+        let mut counts = HashMap::with_capacity(self.cells.len() * 8);
+        let alive = self
+            .cells
+            .iter()
+            .filter(|(_, cell)| matches!(cell, Cell::Alive));
+        for (coords, _) in alive {
+            for y in coords.y - 1..=coords.y + 1 {
+                for x in coords.x - 1..=coords.x + 1 {
+                    if coords.x == x && coords.y == y {
+                        continue;
+                    }
+                    *counts.entry(Coords { x, y }).or_insert(0) += 1;
+                }
+            }
+        }
+        counts
+    }
+}
+
+impl Simulation for SparseState {
+    fn step(&mut self) {
+        let counts = self.count_alive_neighbors();
+        let mut next = HashMap::with_capacity(self.cells.len());
+
+        // existing cells survive, decay or die
+        for (&coords, cell) in &self.cells {
+            let alive_neighbors = counts.get(&coords).copied().unwrap_or(0);
+            let cell = cell.next(alive_neighbors, &self.rule);
+            if !matches!(cell, Cell::Dead) {
+                next.insert(coords, cell);
+            }
+        }
+        // dead cells with living neighbors may be born
+        for (coords, alive_neighbors) in counts {
+            if !self.cells.contains_key(&coords) && self.rule.is_birth(alive_neighbors) {
+                next.insert(coords, Cell::Alive);
+            }
+        }
+
+        self.cells = next;
+        self.generation += 1;
+    }
+
+    fn get_cell(&self, x: i32, y: i32) -> Cell {
+        self.cells
+            .get(&Coords { x, y })
+            .copied()
+            .unwrap_or(Cell::Dead)
+    }
+
+    fn set_cell(&mut self, x: i32, y: i32, value: Cell) {
+        match value {
+            Cell::Dead => self.cells.remove(&Coords { x, y }),
+            _ => self.cells.insert(Coords { x, y }, value),
+        };
+    }
+
+    fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    fn live_cells(&self) -> Box<dyn Iterator<Item = (Coords, Cell)> + '_> {
+        Box::new(self.cells.iter().map(|(&coords, &cell)| (coords, cell)))
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+
+    fn rule(&self) -> &Rule {
+        &self.rule
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Bounds;
+
+    #[test]
+    fn glider_travels_without_wrapping() {
+        let mut state = SparseState::new(Rule::default());
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            state.set_cell(x, y, Cell::Alive);
+        }
+
+        for _ in 0..400 {
+            state.step();
+        }
+
+        assert_eq!(state.generation(), 400);
+        assert_eq!(state.population(), 5);
+        assert_eq!(
+            state.bounding_box(),
+            Some(Bounds {
+                x_min: 100,
+                x_max: 102,
+                y_min: 100,
+                y_max: 102,
+            })
+        );
+    }
+
+    #[test]
+    fn dying_cells_decay_away() {
+        let mut state = SparseState::new("B2/S/C3".parse().unwrap());
+        state.set_cell(0, 0, Cell::Alive);
+
+        state.step();
+        assert_eq!(state.get_cell(0, 0), Cell::Dying(1));
+        state.step();
+        assert_eq!(state.live_cells().count(), 0);
+    }
+}
@@ -1,16 +1,38 @@
-use crate::core::{Config, Coords};
+use crate::core::{Bounds, Config, Coords, Rule, Sample, Simulation, Soup, Stats, Topology};
 use std::iter::Iterator;
-use std::mem;
+use std::ops::Range;
+use std::{mem, thread};
 
 use super::cell::Cell;
 
+// Side of the square tiles the board is split into for activity tracking.
+const TILE: usize = 16;
+// Fewest tile rows in a band stepped by its own thread, below which spawning
+// the thread costs more than it saves.
+const BAND_TILES_MIN: usize = 2;
+
 /// Game of Life state
 ///
 /// Includes board size (expressed in number of columns and rows), current
-/// generation, board state (current and next), and pre-computed neighbor list
-/// for each cell.
+/// generation, the rule driving the evolution, the topology of the board edges,
+/// board state (current and next), pre-computed neighbor list for each cell,
+/// and activity tracking.
+///
+/// The board is split into `TILE x TILE` tiles, and only the dirty ones are
+/// recomputed: those containing or neighboring a cell that changed in the last
+/// generation, or that was edited since. Any other tile is stable, and the
+/// next buffer, which holds the previous generation, already matches it.
+///
+/// Population, births, deaths and bounds of each generation are recorded in
+/// `Stats`, up to the configured number of generations. The population and
+/// the number of non-dead cells of each tile are kept up to date as cells
+/// change, so that bounds are found by scanning the tiles on the edges of the
+/// occupied ones only.
 pub struct State {
-    pub(crate) generation: u32,
+    pub(crate) generation: u64,
+    rule: Rule,
+    topology: Topology,
+    threads: usize,
 
     // Board
     pub(crate) cols: u32,
@@ -19,6 +41,18 @@ pub struct State {
     next: Vec<Cell>,
 
     neighbors: Vec<Vec<usize>>,
+
+    // Activity
+    tiles_x: usize,
+    dirty: Vec<bool>,
+    changed: Vec<usize>,
+    edited: Vec<usize>,
+
+    // Census
+    population: usize,
+    occupied: Vec<u32>,
+
+    stats: Stats,
 }
 
 impl State {
@@ -27,37 +61,44 @@ impl State {
     /// especially in conjunction with mem::swap.
     ///
     /// We also pre-compute the indices of all neighbors for each cell, in order
-    /// to speedup neighbor inspection.
+    /// to speedup neighbor inspection. This is where the topology is applied,
+    /// so that evolution does not need to care about board edges.
     pub fn new(config: &Config) -> Self {
         let cols = config.cols;
         let rows = config.rows;
+        let topology = config.topology;
         //
         let board_capacity = (cols * rows) as usize;
         let curr = vec![Cell::Dead; board_capacity];
         let next = curr.clone();
 
         let neighbors = (0..board_capacity)
-            .map(|i| get_neighbors_indices(i, cols, rows))
+            .map(|i| get_neighbors_indices(i, cols, rows, topology))
             .collect();
 
+        let tiles_x = (cols as usize).div_ceil(TILE);
+        let tiles_y = (rows as usize).div_ceil(TILE);
+
         State {
             generation: 0,
+            rule: config.rule,
+            topology,
+            threads: config.threads.max(1),
             cols,
             rows,
             curr,
             next,
             neighbors,
+            tiles_x,
+            dirty: vec![true; tiles_x * tiles_y],
+            changed: Vec::new(),
+            edited: Vec::new(),
+            population: 0,
+            occupied: vec![0; tiles_x * tiles_y],
+            stats: Stats::new(config.stats),
         }
     }
 
-    /// Returns the number of alive neighbors using the pre-computed neighbors
-    /// index.
-    fn count_alive_neighbors(&self, index: usize) -> u8 {
-        self.neighbors[index]
-            .iter()
-            .fold(0, |count, &neighbor| count + self.curr[neighbor].as_value())
-    }
-
     pub fn iter(&self) -> BoardIterator {
         BoardIterator {
             index: 0,
@@ -66,47 +107,312 @@ impl State {
     }
 
     /// Compute next generation state based on current one.
+    ///
+    /// With more than one thread, the board is split into bands of whole tile
+    /// rows that are stepped in parallel. Each thread only reads the current
+    /// board and writes its own band of the next one, so the result is
+    /// identical to the single-threaded one. Boards too small to give each
+    /// thread a few tile rows use fewer threads, down to one.
     pub fn next(&mut self) {
-        for (i, &cell) in self.curr.iter().enumerate() {
-            let alive_neighbors = self.count_alive_neighbors(i);
-            self.next[i] = cell.next(alive_neighbors);
+        let step = Step {
+            curr: &self.curr,
+            neighbors: &self.neighbors,
+            rule: &self.rule,
+            cols: self.cols as usize,
+            rows: self.rows as usize,
+            tiles_x: self.tiles_x,
+            dirty: &self.dirty,
+        };
+        let tiles_y = self.dirty.len() / self.tiles_x.max(1);
+
+        let band_tiles = tiles_y.div_ceil(self.threads).max(BAND_TILES_MIN);
+
+        let changed = if band_tiles >= tiles_y {
+            step.next_band(0..tiles_y, &mut self.next)
+        } else {
+            let band_len = band_tiles * TILE * step.cols;
+            thread::scope(|scope| {
+                let handles: Vec<_> = self
+                    .next
+                    .chunks_mut(band_len.max(1))
+                    .enumerate()
+                    .map(|(band, next)| {
+                        let tile_rows = band * band_tiles..((band + 1) * band_tiles).min(tiles_y);
+                        scope.spawn(move || step.next_band(tile_rows, next))
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .flat_map(|handle| handle.join().expect("band thread panicked"))
+                    .collect()
+            })
+        };
+
+        // the generation stepped from is sampled too when none is, eg. the
+        // initial one
+        if self.stats.capacity() > 0 && self.stats.is_empty() {
+            let sample = self.sample(0, 0);
+            self.stats.push(sample);
+        }
+
+        self.changed = changed;
+        let (births, deaths) = self.count_changes();
+        self.changed.append(&mut self.edited);
+        self.dirty.fill(false);
+        for i in 0..self.changed.len() {
+            self.mark_dirty(self.changed[i]);
         }
+
         self.generation += 1;
         mem::swap(&mut self.curr, &mut self.next);
+
+        if self.stats.capacity() > 0 {
+            let sample = self.sample(births, deaths);
+            self.stats.push(sample);
+        }
+    }
+
+    /// Counts the cells that became alive and those that stopped being alive
+    /// in the step, before the buffers are swapped, updating the census.
+    fn count_changes(&mut self) -> (usize, usize) {
+        let (mut births, mut deaths) = (0, 0);
+        for k in 0..self.changed.len() {
+            let i = self.changed[k];
+            let (from, to) = (self.curr[i], self.next[i]);
+            births += (to == Cell::Alive) as usize;
+            deaths += (from == Cell::Alive) as usize;
+            self.count(i, from, to);
+        }
+        (births, deaths)
+    }
+
+    /// Updates the census for a cell going from one state to another.
+    fn count(&mut self, index: usize, from: Cell, to: Cell) {
+        let tile = self.tile_of(index);
+        self.population =
+            self.population + (to == Cell::Alive) as usize - (from == Cell::Alive) as usize;
+        self.occupied[tile] =
+            self.occupied[tile] + (to != Cell::Dead) as u32 - (from != Cell::Dead) as u32;
+    }
+
+    /// Finds the bounds of the non-dead cells, scanning the cells of the
+    /// occupied tiles on the edges of the occupied area only.
+    fn bounds(&self) -> Option<Bounds> {
+        let (cols, rows) = (self.cols as usize, self.rows as usize);
+        let tiles = self
+            .occupied
+            .iter()
+            .enumerate()
+            .filter(|&(_, &count)| count > 0)
+            .map(|(tile, _)| Coords {
+                x: (tile % self.tiles_x) as i32,
+                y: (tile / self.tiles_x) as i32,
+            });
+        let area = Bounds::from_coords(tiles.clone())?;
+        let edges = tiles.filter(|tile| {
+            tile.x == area.x_min
+                || tile.x == area.x_max
+                || tile.y == area.y_min
+                || tile.y == area.y_max
+        });
+        let cells = edges.flat_map(|tile| {
+            let (x, y) = (tile.x as usize * TILE, tile.y as usize * TILE);
+            (y..(y + TILE).min(rows))
+                .flat_map(move |y| (x..(x + TILE).min(cols)).map(move |x| y * cols + x))
+        });
+        Bounds::from_coords(
+            cells
+                .filter(|&i| self.curr[i] != Cell::Dead)
+                .map(|i| coords_from_index(i, self.cols)),
+        )
+    }
+
+    /// Samples the current board.
+    fn sample(&self, births: usize, deaths: usize) -> Sample {
+        Sample {
+            generation: self.generation,
+            population: self.population,
+            births,
+            deaths,
+            bounds: self.bounds(),
+        }
+    }
+
+    /// Returns the statistics of the latest generations.
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    /// Returns the index of the tile containing a cell.
+    fn tile_of(&self, index: usize) -> usize {
+        let cols = self.cols as usize;
+        (index / cols / TILE) * self.tiles_x + (index % cols) / TILE
+    }
+
+    /// Marks as dirty the tiles of a cell and of its neighbors.
+    fn mark_dirty(&mut self, index: usize) {
+        let tile = self.tile_of(index);
+        self.dirty[tile] = true;
+        for k in 0..self.neighbors[index].len() {
+            let tile = self.tile_of(self.neighbors[index][k]);
+            self.dirty[tile] = true;
+        }
+    }
+
+    /// Records a manual edit of a cell, whose tile needs recomputing.
+    fn edit(&mut self, index: usize) {
+        self.mark_dirty(index);
+        self.edited.push(index);
+    }
+
+    /// Returns the coordinates of cells that changed in the last generation,
+    /// and of cells edited since then.
+    pub fn changed_cells(&self) -> impl Iterator<Item = Coords> + '_ {
+        self.changed
+            .iter()
+            .chain(self.edited.iter())
+            .map(|&index| coords_from_index(index, self.cols))
+    }
+
+    pub fn rule(&self) -> &Rule {
+        &self.rule
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
     }
 
     /// Updates current board's cell state to match provided coordinates and value.
+    ///
+    /// Coordinates falling off a bounded board are ignored.
     pub fn set_cell(&mut self, x: i32, y: i32, value: Cell) {
-        let index = coords_to_index(x, y, self.cols, self.rows);
-        self.curr[index] = value;
+        if let Some(index) = coords_to_index(x, y, self.cols, self.rows, self.topology) {
+            let from = mem::replace(&mut self.curr[index], value);
+            self.count(index, from, value);
+            self.edit(index);
+        }
     }
 
     /// Flips cell state.
+    ///
+    /// Coordinates falling off a bounded board are ignored.
     pub fn toggle_cell(&mut self, coords: &Coords) {
-        let index = coords_to_index(coords.x, coords.y, self.cols, self.rows);
-        self.curr[index].toggle();
+        let (x, y) = (coords.x, coords.y);
+        if let Some(index) = coords_to_index(x, y, self.cols, self.rows, self.topology) {
+            let from = self.curr[index];
+            self.curr[index].toggle();
+            self.count(index, from, self.curr[index]);
+            self.edit(index);
+        }
     }
 
     /// Fills the entire board with dead cells.
     pub fn clear(&mut self) {
-        self.curr.fill(Cell::Dead);
+        for index in 0..self.curr.len() {
+            if self.curr[index] != Cell::Dead {
+                let from = mem::replace(&mut self.curr[index], Cell::Dead);
+                self.count(index, from, Cell::Dead);
+                self.edit(index);
+            }
+        }
     }
 
-    pub fn create_coords(&self, x: i32, y: i32) -> Coords {
-        Coords {
-            x: x.rem_euclid(self.cols as i32),
-            y: y.rem_euclid(self.rows as i32),
+    /// Replaces the cells of the soup area with a random soup, leaving the rest
+    /// of the board untouched. The same soup always yields the same board.
+    pub fn randomize(&mut self, soup: &Soup) {
+        let area = soup.area(self.cols, self.rows);
+        for y in area.y_min..=area.y_max {
+            for x in area.x_min..=area.x_max {
+                self.set_cell(x, y, Cell::Dead);
+            }
+        }
+        for Coords { x, y } in soup.cells(self.cols, self.rows) {
+            self.set_cell(x, y, Cell::Alive);
         }
     }
 
-    pub fn wrap_coords(&self, coords: &Coords) -> Coords {
-        Coords {
-            x: coords.x.rem_euclid(self.cols as i32),
-            y: coords.y.rem_euclid(self.rows as i32),
-        }
+    /// Creates board coordinates from (x,y), wrapping them according to the
+    /// topology. Returns `None` when they fall off a bounded board.
+    pub fn create_coords(&self, x: i32, y: i32) -> Option<Coords> {
+        self.topology
+            .wrap(x, y, self.cols, self.rows)
+            .map(Coords::from)
+    }
+
+    /// Wraps coordinates according to the topology. Returns `None` when they
+    /// fall off a bounded board.
+    pub fn wrap_coords(&self, coords: &Coords) -> Option<Coords> {
+        self.create_coords(coords.x, coords.y)
+    }
+}
+
+impl Simulation for State {
+    fn step(&mut self) {
+        self.next();
+    }
+
+    fn get_cell(&self, x: i32, y: i32) -> Cell {
+        coords_to_index(x, y, self.cols, self.rows, self.topology)
+            .map(|index| self.curr[index])
+            .unwrap_or(Cell::Dead)
+    }
+
+    fn set_cell(&mut self, x: i32, y: i32, value: Cell) {
+        State::set_cell(self, x, y, value);
+    }
+
+    fn toggle_cell(&mut self, coords: &Coords) {
+        State::toggle_cell(self, coords);
+    }
+
+    fn clear(&mut self) {
+        State::clear(self);
+    }
+
+    fn live_cells(&self) -> Box<dyn Iterator<Item = (Coords, Cell)> + '_> {
+        Box::new(
+            self.iter()
+                .filter(|(_, cell)| !matches!(cell, Cell::Dead))
+                .map(|(coords, &cell)| (coords, cell)),
+        )
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+        self.stats.truncate(generation);
+    }
+
+    fn rule(&self) -> &Rule {
+        &self.rule
+    }
+
+    fn bounding_box(&self) -> Option<Bounds> {
+        self.bounds()
+    }
+
+    fn population(&self) -> usize {
+        self.population
+    }
+
+    fn wrap_coords(&self, coords: &Coords) -> Option<Coords> {
+        State::wrap_coords(self, coords)
+    }
+
+    fn changed_cells(&self) -> Option<Box<dyn Iterator<Item = Coords> + '_>> {
+        Some(Box::new(State::changed_cells(self)))
+    }
+
+    fn stats(&self) -> Option<&Stats> {
+        Some(&self.stats)
     }
 }
 
+/// Iterates over every cell of the board, row by row, yielding its coordinates
+/// along with the cell, whose `Cell::state` is the Generations state index.
 pub struct BoardIterator<'a> {
     index: usize,
     state: &'a State,
@@ -127,8 +433,61 @@ impl<'a> Iterator for BoardIterator<'a> {
     }
 }
 
+/// Read-only view of the state needed to compute the next generation, shared
+/// by the threads.
+#[derive(Clone, Copy)]
+struct Step<'a> {
+    curr: &'a [Cell],
+    neighbors: &'a [Vec<usize>],
+    rule: &'a Rule,
+    cols: usize,
+    rows: usize,
+    tiles_x: usize,
+    dirty: &'a [bool],
+}
+
+impl Step<'_> {
+    /// Computes the next state of the dirty tiles within a band of tile rows
+    /// into `next`, which holds the band cells only. Returns the indices of the
+    /// cells that changed.
+    fn next_band(&self, tile_rows: Range<usize>, next: &mut [Cell]) -> Vec<usize> {
+        let start = tile_rows.start * TILE * self.cols;
+        let mut changed = Vec::new();
+
+        for tile_y in tile_rows {
+            for tile_x in (0..self.tiles_x).filter(|tx| self.dirty[tile_y * self.tiles_x + tx]) {
+                let ys = tile_y * TILE..((tile_y + 1) * TILE).min(self.rows);
+                for y in ys {
+                    let xs = tile_x * TILE..((tile_x + 1) * TILE).min(self.cols);
+                    for index in xs.map(|x| y * self.cols + x) {
+                        let alive_neighbors =
+                            count_alive_neighbors(self.curr, self.neighbors, index);
+                        let cell = self.curr[index].next(alive_neighbors, self.rule);
+                        if cell != self.curr[index] {
+                            changed.push(index);
+                        }
+                        next[index - start] = cell;
+                    }
+                }
+            }
+        }
+        changed
+    }
+}
+
+/// Returns the number of alive neighbors using the pre-computed neighbors
+/// index.
+fn count_alive_neighbors(curr: &[Cell], neighbors: &[Vec<usize>], index: usize) -> u8 {
+    neighbors[index]
+        .iter()
+        .fold(0, |count, &neighbor| count + curr[neighbor].as_value())
+}
+
 /// Returns a vector containing current cell's neighbors indices.
 ///
+/// Neighbors falling off a bounded board are left out, as well as neighbors
+/// that a twisted topology maps back onto the cell itself.
+///
 /// ```txt
 /// +-----+-----+-----+
 /// | nw  |  n  | ne  |
@@ -139,7 +498,7 @@ impl<'a> Iterator for BoardIterator<'a> {
 /// +-----+-----+-----+
 /// ```
 ///
-fn get_neighbors_indices(index: usize, cols: u32, rows: u32) -> Vec<usize> {
+fn get_neighbors_indices(index: usize, cols: u32, rows: u32, topology: Topology) -> Vec<usize> {
     let coords = coords_from_index(index, cols);
     let mut indices = Vec::with_capacity(8);
     for y in coords.y - 1..=coords.y + 1 {
@@ -147,7 +506,10 @@ fn get_neighbors_indices(index: usize, cols: u32, rows: u32) -> Vec<usize> {
             if coords.x == x && coords.y == y {
                 continue;
             }
-            indices.push(coords_to_index(x, y, cols, rows))
+            match coords_to_index(x, y, cols, rows, topology) {
+                Some(neighbor) if neighbor != index => indices.push(neighbor),
+                _ => {}
+            }
         }
     }
     indices
@@ -162,12 +524,221 @@ pub fn coords_from_index(index: usize, cols: u32) -> Coords {
 
 /// Converts 2D coordinates (x, y) into Vector index.
 ///
-/// The conversion is wrapping, meaning that `x` values greater than `cols` or
-/// less than zero are always converted into ranges `0..=cols`. The same applies
-/// to `y` and `rows`.
-pub fn coords_to_index(x: i32, y: i32, cols: u32, rows: u32) -> usize {
-    let x = x.rem_euclid(cols as i32) as usize;
-    let y = y.rem_euclid(rows as i32) as usize;
-
-    cols as usize * y + x
+/// The conversion is wrapping according to `topology`, meaning that on a torus
+/// `x` values greater than `cols` or less than zero are always converted into
+/// ranges `0..=cols`, and the same applies to `y` and `rows`. Returns `None`
+/// when the coordinates fall off a bounded board.
+pub fn coords_to_index(x: i32, y: i32, cols: u32, rows: u32, topology: Topology) -> Option<usize> {
+    let (x, y) = topology.wrap(x, y, cols, rows)?;
+
+    Some(cols as usize * y as usize + x as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Bounds;
+
+    fn glider(config: &Config) -> State {
+        let mut state = State::new(config);
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            state.set_cell(x, y, Cell::Alive);
+        }
+        state
+    }
+
+    fn alive_cells(state: &State) -> Vec<Coords> {
+        state
+            .iter()
+            .filter(|(_, cell)| matches!(cell, Cell::Alive))
+            .map(|(coords, _)| coords)
+            .collect()
+    }
+
+    #[test]
+    fn glider_re_enters_a_torus() {
+        let config = Config {
+            cols: 8,
+            rows: 8,
+            ..Config::default()
+        };
+        let mut state = glider(&config);
+        let initial = alive_cells(&state);
+
+        // a glider travels one cell diagonally every 4 generations
+        for _ in 0..4 * 8 {
+            state.next();
+        }
+        assert_eq!(alive_cells(&state), initial);
+    }
+
+    #[test]
+    fn glider_leaves_a_plane() {
+        let config = Config {
+            cols: 8,
+            rows: 8,
+            topology: Topology::Plane,
+            ..Config::default()
+        };
+        let mut state = glider(&config);
+        let initial = alive_cells(&state);
+
+        for _ in 0..4 * 8 {
+            state.next();
+        }
+        assert_ne!(alive_cells(&state), initial);
+        assert_eq!(state.create_coords(8, 0), None);
+    }
+
+    #[test]
+    fn tracks_changed_cells() {
+        let mut state = State::new(&Config::default());
+        // blinker, two cells die and two are born every generation
+        for x in 40..=42 {
+            state.set_cell(x, 10, Cell::Alive);
+        }
+        assert_eq!(state.changed_cells().count(), 3);
+
+        // edits made before the step are still reported, renderers may have
+        // not drawn them yet
+        state.next();
+        let mut changed: Vec<_> = state.changed_cells().map(|c| (c.x, c.y)).collect();
+        changed.sort();
+        changed.dedup();
+        assert_eq!(
+            changed,
+            vec![(40, 10), (41, 9), (41, 10), (41, 11), (42, 10)]
+        );
+
+        state.next();
+        assert_eq!(state.changed_cells().count(), 4);
+    }
+
+    #[test]
+    fn records_stats() {
+        let mut state = State::new(&Config::default());
+        for x in 40..=42 {
+            state.set_cell(x, 10, Cell::Alive);
+        }
+        state.next();
+        state.next();
+
+        let stats = state.stats();
+        assert_eq!(stats.len(), 3);
+        assert_eq!(
+            stats.iter().next().map(|s| (s.generation, s.population)),
+            Some((0, 3))
+        );
+        assert_eq!(
+            stats.latest(),
+            Some(&Sample {
+                generation: 2,
+                population: 3,
+                births: 2,
+                deaths: 2,
+                bounds: Some(Bounds {
+                    x_min: 40,
+                    x_max: 42,
+                    y_min: 10,
+                    y_max: 10
+                }),
+            })
+        );
+
+        let disabled = State::new(&Config {
+            stats: 0,
+            ..Config::default()
+        });
+        assert!(disabled.stats().is_empty());
+    }
+
+    #[test]
+    fn keeps_census_up_to_date() {
+        // Star Wars, whose dying cells count in the bounds only
+        let mut state = State::new(&Config {
+            rule: "B2/S345/C4".parse().unwrap(),
+            ..Config::default()
+        });
+        for generation in 0..100 {
+            if generation % 10 == 0 {
+                state.clear();
+                state.toggle_cell(&Coords { x: 79, y: 24 });
+                state.randomize(&Soup::new(generation));
+            }
+            state.next();
+            let live = || state.live_cells().map(|(coords, _)| coords);
+            assert_eq!(state.bounding_box(), Bounds::from_coords(live()));
+            assert_eq!(state.population(), alive_cells(&state).len());
+        }
+    }
+
+    #[test]
+    fn skips_stable_tiles() {
+        let mut state = State::new(&Config::default());
+        // block, a still life
+        for (x, y) in [(5, 5), (6, 5), (5, 6), (6, 6)] {
+            state.set_cell(x, y, Cell::Alive);
+        }
+
+        state.next();
+        assert_eq!(state.changed_cells().count(), 4);
+        state.next();
+        assert_eq!(state.changed_cells().count(), 0);
+        assert!(state.dirty.iter().all(|dirty| !dirty));
+
+        // editing far away wakes up the edited tile only
+        state.set_cell(70, 20, Cell::Alive);
+        state.next();
+        assert_eq!(alive_cells(&state).len(), 4);
+    }
+
+    #[test]
+    fn threads_agree_with_single_thread() {
+        let config = |threads| Config {
+            cols: 97,
+            rows: 129,
+            threads,
+            ..Config::default()
+        };
+        let mut single = State::new(&config(1));
+        let mut multi = State::new(&config(4));
+
+        // R-pentomino, a chaotic methuselah crossing the band edges
+        for (x, y) in [(49, 64), (50, 64), (48, 65), (49, 65), (49, 66)] {
+            single.set_cell(x, y, Cell::Alive);
+            multi.set_cell(x, y, Cell::Alive);
+        }
+
+        for _ in 0..200 {
+            single.next();
+            multi.next();
+            assert_eq!(alive_cells(&multi), alive_cells(&single));
+        }
+    }
+
+    #[test]
+    fn randomize_replaces_the_area_only() {
+        let mut state = State::new(&Config::default());
+        state.set_cell(0, 0, Cell::Alive);
+        let soup = Soup {
+            density: 1.0,
+            area: Some(Bounds {
+                x_min: 10,
+                x_max: 19,
+                y_min: 5,
+                y_max: 9,
+            }),
+            ..Soup::new(7)
+        };
+        state.randomize(&soup);
+        assert_eq!(state.population(), 51);
+
+        let (mut a, mut b) = (
+            State::new(&Config::default()),
+            State::new(&Config::default()),
+        );
+        a.randomize(&Soup::new(7));
+        b.randomize(&Soup::new(7));
+        assert_eq!(alive_cells(&a), alive_cells(&b));
+    }
 }
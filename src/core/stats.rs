@@ -0,0 +1,178 @@
+//! Per-generation statistics, kept in a ring buffer.
+
+use std::{
+    collections::VecDeque,
+    io::{self, Write},
+};
+
+use super::bounds::Bounds;
+
+/// Statistics of a single generation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Sample {
+    pub generation: u64,
+    // Alive cells, dying ones excluded.
+    pub population: usize,
+    // Cells that became alive during the step leading to this generation.
+    pub births: usize,
+    // Cells that stopped being alive during that step.
+    pub deaths: usize,
+    // Bounds of the non-dead cells, `None` on an empty board.
+    pub bounds: Option<Bounds>,
+}
+
+/// Time series of the latest generations, oldest first. Once full, each new
+/// sample replaces the oldest one.
+#[derive(Clone, Debug)]
+pub struct Stats {
+    capacity: usize,
+    samples: VecDeque<Sample>,
+}
+
+impl Stats {
+    pub fn new(capacity: usize) -> Self {
+        Stats {
+            capacity,
+            samples: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn push(&mut self, sample: Sample) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Returns the sample of the latest generation.
+    pub fn latest(&self) -> Option<&Sample> {
+        self.samples.back()
+    }
+
+    /// Returns the sample of a generation, if still retained.
+    pub fn get(&self, generation: u64) -> Option<&Sample> {
+        self.samples
+            .binary_search_by_key(&generation, |sample| sample.generation)
+            .ok()
+            .map(|i| &self.samples[i])
+    }
+
+    /// Drops the samples of the generations after the given one, eg. when the
+    /// generation counter is moved backwards.
+    pub fn truncate(&mut self, generation: u64) {
+        let len = self
+            .samples
+            .partition_point(|sample| sample.generation <= generation);
+        self.samples.truncate(len);
+    }
+
+    /// Iterates over the samples, oldest first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Sample> + ExactSizeIterator {
+        self.samples.iter()
+    }
+
+    /// Writes the samples as CSV, with a header line. Bounds of empty boards
+    /// are left empty.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(
+            writer,
+            "generation,population,births,deaths,x_min,x_max,y_min,y_max"
+        )?;
+        for sample in &self.samples {
+            let bounds = match sample.bounds {
+                Some(b) => format!("{},{},{},{}", b.x_min, b.x_max, b.y_min, b.y_max),
+                None => ",,,".to_string(),
+            };
+            writeln!(
+                writer,
+                "{},{},{},{},{}",
+                sample.generation, sample.population, sample.births, sample.deaths, bounds
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = Vec::new();
+        self.write_csv(&mut csv)
+            .expect("writing to a Vec never fails");
+        String::from_utf8(csv).expect("CSV is valid UTF-8")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(generation: u64, population: usize) -> Sample {
+        Sample {
+            generation,
+            population,
+            births: 0,
+            deaths: 0,
+            bounds: None,
+        }
+    }
+
+    #[test]
+    fn keeps_latest_samples() {
+        let mut stats = Stats::new(3);
+        for generation in 1..=5 {
+            stats.push(sample(generation, generation as usize * 10));
+        }
+        assert_eq!(stats.len(), 3);
+        assert_eq!(stats.latest().map(|s| s.population), Some(50));
+        assert_eq!(stats.get(3).map(|s| s.population), Some(30));
+        assert_eq!(stats.get(2), None);
+        assert_eq!(stats.get(6), None);
+
+        stats.truncate(3);
+        assert_eq!(stats.latest().map(|s| s.generation), Some(3));
+
+        let mut disabled = Stats::new(0);
+        disabled.push(sample(1, 1));
+        assert!(disabled.is_empty());
+    }
+
+    #[test]
+    fn exports_csv() {
+        let mut stats = Stats::new(4);
+        stats.push(sample(1, 0));
+        stats.push(Sample {
+            births: 3,
+            deaths: 1,
+            bounds: Some(Bounds {
+                x_min: 1,
+                x_max: 3,
+                y_min: -2,
+                y_max: 0,
+            }),
+            ..sample(2, 2)
+        });
+        assert_eq!(
+            stats.to_csv(),
+            "generation,population,births,deaths,x_min,x_max,y_min,y_max\n\
+             1,0,0,0,,,,\n\
+             2,2,3,1,1,3,-2,0\n"
+        );
+    }
+}
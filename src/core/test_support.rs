@@ -0,0 +1,28 @@
+//! Fixtures shared by the tests of the modules working on any engine.
+
+use super::{Cell, Rule, Simulation, SparseState, format::Pattern};
+
+/// Returns an unbounded board with the given cells alive, under the default
+/// rule.
+pub(crate) fn sparse(cells: &[(i32, i32)]) -> SparseState {
+    let mut sparse = SparseState::new(Rule::default());
+    for &(x, y) in cells {
+        sparse.set_cell(x, y, Cell::Alive);
+    }
+    sparse
+}
+
+/// Returns an unbounded board holding a pattern with its top-left corner at
+/// (0,0), under the default rule.
+pub(crate) fn sparse_pattern(pattern: &Pattern) -> SparseState {
+    let mut sparse = SparseState::new(Rule::default());
+    pattern.place(&mut sparse, 0, 0);
+    sparse
+}
+
+/// Returns the coords of the non-dead cells of a simulation, sorted.
+pub(crate) fn live_coords<S: Simulation + ?Sized>(sim: &S) -> Vec<(i32, i32)> {
+    let mut cells: Vec<_> = sim.live_cells().map(|(c, _)| (c.x, c.y)).collect();
+    cells.sort();
+    cells
+}
@@ -0,0 +1,155 @@
+//! The topology describes how the edges of a finite board are glued together,
+//! which decides where the neighbors of a border cell are.
+//!
+//! ```txt
+//!          torus        plane      cylinder     klein bottle   cross-surface
+//!        +-->--+      +-----+      +-----+        +-->--+         +-->--+
+//!        ^     ^      |     |      ^     ^        ^     ^         ^     v
+//!        +-->--+      +-----+      +-----+        +--<--+         +--<--+
+//! ```
+//!
+//! Arrows on opposite edges pointing the same way are glued as they are, while
+//! arrows pointing opposite ways are glued after a flip. Edges without arrows
+//! are bordered by dead cells, so patterns can leave the board.
+
+use std::{fmt, str::FromStr};
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Topology {
+    // Both axes wrap, patterns leaving an edge re-enter from the opposite one.
+    #[default]
+    Torus,
+    // Nothing wraps, the board is surrounded by dead cells.
+    Plane,
+    // Columns wrap, while top and bottom edges are surrounded by dead cells.
+    Cylinder,
+    // Columns wrap, rows wrap flipping the horizontal axis.
+    KleinBottle,
+    // Both axes wrap, each one flipping the other axis.
+    CrossSurface,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ParseTopologyError;
+
+impl Topology {
+    /// Maps (x,y) coordinates, possibly outside the board, onto the board.
+    ///
+    /// Returns `None` when the coordinates fall off the edge of a bounded
+    /// topology.
+    pub fn wrap(&self, x: i32, y: i32, cols: u32, rows: u32) -> Option<(i32, i32)> {
+        let (cols, rows) = (cols as i32, rows as i32);
+        let x_inside = (0..cols).contains(&x);
+        let y_inside = (0..rows).contains(&y);
+
+        // Number of times an edge is crossed, an odd count means flipping.
+        let x_flipped = x.div_euclid(cols) % 2 != 0;
+        let y_flipped = y.div_euclid(rows) % 2 != 0;
+        let flip = |value: i32, max: i32| max - 1 - value;
+
+        match self {
+            Topology::Torus => Some((x.rem_euclid(cols), y.rem_euclid(rows))),
+            Topology::Plane => (x_inside && y_inside).then_some((x, y)),
+            Topology::Cylinder => y_inside.then_some((x.rem_euclid(cols), y)),
+            Topology::KleinBottle => {
+                let x = if y_flipped { flip(x, cols) } else { x };
+                Some((x.rem_euclid(cols), y.rem_euclid(rows)))
+            }
+            Topology::CrossSurface => {
+                let (x, y) = (x.rem_euclid(cols), y.rem_euclid(rows));
+                let x = if y_flipped { flip(x, cols) } else { x };
+                let y = if x_flipped { flip(y, rows) } else { y };
+                Some((x, y))
+            }
+        }
+    }
+}
+
+impl FromStr for Topology {
+    type Err = ParseTopologyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "torus" => Ok(Topology::Torus),
+            "plane" => Ok(Topology::Plane),
+            "cylinder" => Ok(Topology::Cylinder),
+            "klein" | "klein-bottle" => Ok(Topology::KleinBottle),
+            "cross" | "cross-surface" => Ok(Topology::CrossSurface),
+            _ => Err(ParseTopologyError),
+        }
+    }
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Topology::Torus => "torus",
+            Topology::Plane => "plane",
+            Topology::Cylinder => "cylinder",
+            Topology::KleinBottle => "klein-bottle",
+            Topology::CrossSurface => "cross-surface",
+        };
+        f.write_str(name)
+    }
+}
+
+impl fmt::Display for ParseTopologyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "topology must be one of torus, plane, cylinder, klein-bottle, cross-surface"
+        )
+    }
+}
+
+impl std::error::Error for ParseTopologyError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_coords_inside_the_board() {
+        for topology in [
+            Topology::Torus,
+            Topology::Plane,
+            Topology::Cylinder,
+            Topology::KleinBottle,
+            Topology::CrossSurface,
+        ] {
+            assert_eq!(topology.wrap(2, 3, 5, 4), Some((2, 3)));
+        }
+    }
+
+    #[test]
+    fn wraps_torus_edges() {
+        assert_eq!(Topology::Torus.wrap(-1, 4, 5, 4), Some((4, 0)));
+    }
+
+    #[test]
+    fn falls_off_bounded_edges() {
+        assert_eq!(Topology::Plane.wrap(-1, 0, 5, 4), None);
+        assert_eq!(Topology::Plane.wrap(0, 4, 5, 4), None);
+        assert_eq!(Topology::Cylinder.wrap(-1, 0, 5, 4), Some((4, 0)));
+        assert_eq!(Topology::Cylinder.wrap(0, -1, 5, 4), None);
+    }
+
+    #[test]
+    fn flips_when_crossing_twisted_edges() {
+        // crossing the top edge of a klein bottle flips the horizontal axis
+        assert_eq!(Topology::KleinBottle.wrap(1, -1, 5, 4), Some((3, 3)));
+        assert_eq!(Topology::KleinBottle.wrap(-1, 1, 5, 4), Some((4, 1)));
+
+        // a cross-surface flips both axes
+        assert_eq!(Topology::CrossSurface.wrap(1, -1, 5, 4), Some((3, 3)));
+        assert_eq!(Topology::CrossSurface.wrap(-1, 1, 5, 4), Some((4, 2)));
+    }
+
+    #[test]
+    fn parses_topology_names() {
+        assert_eq!("Torus".parse(), Ok(Topology::Torus));
+        assert_eq!("klein".parse(), Ok(Topology::KleinBottle));
+        assert_eq!("cross-surface".parse(), Ok(Topology::CrossSurface));
+        assert_eq!("sphere".parse::<Topology>(), Err(ParseTopologyError));
+    }
+}
@@ -1,3 +1,4 @@
 pub mod base64;
+pub mod cli;
 pub mod core;
 pub mod render;
@@ -1,3 +1,4 @@
 pub mod ascii;
 pub mod kitty;
+#[cfg(feature = "sdl")]
 pub mod sdl;
@@ -1,13 +1,20 @@
 use core::str;
 
-use crate::core::{Cell, State};
+use crate::core::{Cell, Coords, Cycle, Simulation};
 
 const ALIVE: u8 = b'@';
 const DEAD: u8 = b'.';
+// Dying states shade from the most recently alive to the closest to death.
+const DYING: &[u8] = b"O*+:";
 
+/// Frame shows the `cols x rows` region of the simulation starting at (0,0).
 pub struct Frame {
     buffer: Vec<u8>,
-    generation: u32,
+    cols: u32,
+    cycle: Option<Cycle>,
+    drawn: bool,
+    generation: u64,
+    rows: u32,
 }
 
 impl Frame {
@@ -15,38 +22,81 @@ impl Frame {
         Frame {
             // add `rows` because the buffer must contain linefeed characters for each row
             buffer: vec![DEAD; (cols * rows + rows) as usize],
+            cols,
+            cycle: None,
+            drawn: false,
             generation: 0,
+            rows,
         }
     }
+
+    /// Sets the cycle reported in the footer, if the board settled.
+    pub fn set_cycle(&mut self, cycle: Option<Cycle>) {
+        self.cycle = cycle;
+    }
 }
 
-fn cell_to_ascii(cell: &Cell) -> u8 {
+fn cell_to_ascii(cell: &Cell, states: u8) -> u8 {
     match cell {
         Cell::Alive => ALIVE,
         Cell::Dead => DEAD,
+        Cell::Dying(n) => {
+            // spread the dying states over the available characters
+            let dying_states = states.saturating_sub(2).max(1) as usize;
+            let shade = (*n as usize - 1) * DYING.len() / dying_states;
+            DYING[shade.min(DYING.len() - 1)]
+        }
     }
 }
 
 pub fn draw_ascii(frame: &Frame) {
-    let Frame { buffer, generation } = frame;
+    let Frame {
+        buffer,
+        cycle,
+        generation,
+        ..
+    } = frame;
     println!("\x1b[3J\x1b[H\x1b[2J"); // clear terminal
     let frame = str::from_utf8(buffer).expect("");
     println!("{frame}");
-    println!("Generation: {generation}");
+    match cycle {
+        Some(cycle) => println!("Generation: {generation} ({cycle})"),
+        None => println!("Generation: {generation}"),
+    }
 }
 
-pub fn render_ascii(frame: &mut Frame, state: &State) {
-    let cols = state.cols as usize;
+/// Renders the simulation into the frame.
+///
+/// When the engine tracks changed cells and the frame already shows the
+/// previous generation, only the changed cells are redrawn.
+pub fn render_ascii<S: Simulation + ?Sized>(frame: &mut Frame, state: &S) {
+    let states = state.rule().states();
+    let generation = state.generation();
+    let is_consecutive =
+        frame.drawn && (generation == frame.generation || generation == frame.generation + 1);
+
+    if let Some(changed) = state.changed_cells().filter(|_| is_consecutive) {
+        for Coords { x, y } in changed {
+            if (0..frame.cols as i32).contains(&x) && (0..frame.rows as i32).contains(&y) {
+                let i = y as usize * (frame.cols as usize + 1) + x as usize;
+                frame.buffer[i] = cell_to_ascii(&state.get_cell(x, y), states);
+            }
+        }
+        frame.generation = generation;
+        return;
+    }
+
     let mut i = 0;
 
-    for row in state.curr.chunks_exact(cols) {
-        for cell in row {
-            frame.buffer[i] = cell_to_ascii(cell);
+    for y in 0..frame.rows as i32 {
+        for x in 0..frame.cols as i32 {
+            frame.buffer[i] = cell_to_ascii(&state.get_cell(x, y), states);
             i += 1;
         }
         frame.buffer[i] = b'\n';
         i += 1;
     }
 
-    frame.generation = state.generation;
+    frame.drawn = true;
+    frame.generation = generation;
 }
@@ -2,7 +2,7 @@ use std::io::Write;
 
 use crate::{
     base64,
-    core::{Cell, State},
+    core::{Cell, Simulation},
 };
 
 const ALIVE: u8 = 0xff;
@@ -12,11 +12,14 @@ const KITTY_IMG_ID: u8 = 246;
 const KITTY_QUIET: u8 = 2;
 const RGB_BYTES: u8 = 3;
 
+/// Frame shows the `cols x rows` region of the simulation starting at (0,0).
 pub struct Frame {
     buffer: Vec<u8>,
     chunk_alive: Vec<u8>,
-    generation: u32,
+    cols: u32,
+    generation: u64,
     height: u32,
+    rows: u32,
     scale: u8,
     width: u32,
 }
@@ -32,7 +35,9 @@ impl Frame {
             // it can be easily copied into the buffer, whenever a living cell
             // is encountered.
             chunk_alive: vec![ALIVE; (RGB_BYTES * scale) as usize],
+            cols,
             generation: 0,
+            rows,
             scale,
             width: cols * scale as u32,
             height: rows * scale as u32,
@@ -40,21 +45,28 @@ impl Frame {
     }
 }
 
-pub fn render_kitty(frame: &mut Frame, state: &State) {
+pub fn render_kitty<S: Simulation + ?Sized>(frame: &mut Frame, state: &S) {
     frame.buffer.fill(DEAD);
 
     let vert_scale_iterations = (frame.scale - 1) as usize;
     let chunk_len = frame.chunk_alive.len();
+    let states = state.rule().states();
     let mut buf_cur = 0;
 
-    for row in state.curr.chunks_exact(state.cols as usize) {
+    for y in 0..frame.rows as i32 {
         let row_cur = buf_cur;
 
-        for cell in row {
+        for x in 0..frame.cols as i32 {
             // Skip dead cells, because the background is set to dead. This
             // potentially saves a bunch of writes.
-            if matches!(cell, Cell::Alive) {
-                frame.buffer[buf_cur..buf_cur + chunk_len].copy_from_slice(&frame.chunk_alive);
+            match state.get_cell(x, y) {
+                Cell::Alive => {
+                    frame.buffer[buf_cur..buf_cur + chunk_len].copy_from_slice(&frame.chunk_alive)
+                }
+                Cell::Dying(n) => {
+                    frame.buffer[buf_cur..buf_cur + chunk_len].fill(dying_shade(n, states))
+                }
+                Cell::Dead => {}
             }
 
             buf_cur += chunk_len;
@@ -70,7 +82,14 @@ pub fn render_kitty(frame: &mut Frame, state: &State) {
         buf_cur += (buf_cur - row_cur) * (vert_scale_iterations);
     }
 
-    frame.generation = state.generation;
+    frame.generation = state.generation();
+}
+
+/// Dying cells are drawn in gray levels fading from `ALIVE` towards `DEAD` as
+/// they get closer to death.
+fn dying_shade(n: u8, states: u8) -> u8 {
+    let range = (ALIVE - DEAD) as u32;
+    DEAD + (range * states.saturating_sub(n + 1) as u32 / states as u32) as u8
 }
 
 pub fn draw_kitty(frame: &Frame) -> Result<(), std::io::Error> {
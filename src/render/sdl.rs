@@ -1,6 +1,9 @@
+pub mod camera;
 pub mod command;
 pub mod game;
+pub mod history;
 pub mod layout;
+pub mod register;
 pub mod renderer;
 pub mod selection;
 pub mod theme;
@@ -0,0 +1,264 @@
+//! Camera over the board, mapping board cells to window pixels and back.
+//!
+//! The camera looks at the board from a position, the board coords shown at
+//! the top-left corner of the view, and a zoom level. When zoomed in, each
+//! cell covers a square of pixels; when zoomed out below one pixel per cell,
+//! each pixel covers a square of cells and is shaded by how many of them are
+//! alive.
+//!
+//! Zooming out halves the pixels per cell, then doubles the cells per pixel
+//! once down to one pixel per cell. Zooming in does the opposite. Either way
+//! stops at the zoom level the game started with when passing it, so that it
+//! is found again.
+
+use crate::core::{Bounds, Coords};
+
+// Largest number of pixels per cell.
+const PIXELS_MAX: u32 = 64;
+// Largest number of cells per pixel, a power of two.
+const CELLS_MAX: u32 = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+    // Board coords at the top-left corner of the view, possibly in between
+    // cells after panning at a zoom level below one pixel per cell.
+    x: f64,
+    y: f64,
+    // Only one of `pixels` and `cells` is greater than 1.
+    pixels: u32,
+    cells: u32,
+    // Pixels per cell the camera started with.
+    home: u32,
+}
+
+impl Camera {
+    /// Returns a camera showing the board from (0,0), with `scale` pixels per
+    /// cell.
+    pub fn new(scale: u32) -> Self {
+        let pixels = scale.clamp(1, PIXELS_MAX);
+        Camera {
+            x: 0.0,
+            y: 0.0,
+            pixels,
+            cells: 1,
+            home: pixels,
+        }
+    }
+
+    /// Side of the square of pixels covered by a cell.
+    pub fn pixels_per_cell(&self) -> u32 {
+        self.pixels
+    }
+
+    /// Side of the square of cells covered by a pixel.
+    pub fn cells_per_pixel(&self) -> u32 {
+        self.cells
+    }
+
+    /// Returns the board coords of the cell under a pixel of the view.
+    pub fn screen_to_cell(&self, pixel: Coords) -> Coords {
+        let (x, y) = self.board_coords(pixel);
+        Coords {
+            x: x.floor() as i32,
+            y: y.floor() as i32,
+        }
+    }
+
+    /// Returns the pixel of the view at the top-left corner of a cell.
+    pub fn cell_to_screen(&self, cell: Coords) -> Coords {
+        let to_pixels = |cells: f64| (cells * self.pixels as f64 / self.cells as f64).floor();
+        Coords {
+            x: to_pixels(cell.x as f64 - self.x) as i32,
+            y: to_pixels(cell.y as f64 - self.y) as i32,
+        }
+    }
+
+    /// Returns the bounds of the cells visible in a view of the given size,
+    /// including the ones partially visible.
+    pub fn visible(&self, width: u32, height: u32) -> Bounds {
+        let top_left = self.screen_to_cell(Coords { x: 0, y: 0 });
+        let bottom_right = self.screen_to_cell(Coords {
+            x: width.saturating_sub(1) as i32,
+            y: height.saturating_sub(1) as i32,
+        });
+        Bounds {
+            x_min: top_left.x,
+            x_max: bottom_right.x,
+            y_min: top_left.y,
+            y_max: bottom_right.y,
+        }
+    }
+
+    /// Moves the view by the given number of pixels, eg. `(1,0)` shows what
+    /// lies one pixel further right.
+    pub fn pan(&mut self, dx: i32, dy: i32) {
+        self.x += self.in_cells(dx);
+        self.y += self.in_cells(dy);
+    }
+
+    /// Zooms in, keeping the cell under the `anchor` pixel in place. Returns
+    /// false when already at the largest zoom level.
+    pub fn zoom_in(&mut self, anchor: Coords) -> bool {
+        if self.cells > 1 {
+            self.zoom_to(anchor, self.pixels, self.cells / 2)
+        } else if self.pixels < PIXELS_MAX {
+            let pixels = self.via_home(self.pixels * 2).min(PIXELS_MAX);
+            self.zoom_to(anchor, pixels, 1)
+        } else {
+            false
+        }
+    }
+
+    /// Zooms out, keeping the cell under the `anchor` pixel in place. Returns
+    /// false when already at the smallest zoom level.
+    pub fn zoom_out(&mut self, anchor: Coords) -> bool {
+        if self.pixels > 1 {
+            let pixels = self.via_home(self.pixels / 2);
+            self.zoom_to(anchor, pixels, 1)
+        } else if self.cells < CELLS_MAX {
+            self.zoom_to(anchor, 1, self.cells * 2)
+        } else {
+            false
+        }
+    }
+
+    /// Picks the largest zoom level showing all the cells within `bounds` in a
+    /// view of the given size, then centres them.
+    ///
+    /// Bounds larger than the view at the smallest zoom level are centred
+    /// anyway, their edges falling off the view.
+    pub fn fit(&mut self, bounds: Bounds, width: u32, height: u32) {
+        let (cols, rows) = (bounds.width(), bounds.height());
+        let fits = |pixels: u32, cells: u32| {
+            cols.div_ceil(cells) * pixels <= width && rows.div_ceil(cells) * pixels <= height
+        };
+
+        (self.pixels, self.cells) = match (1..=PIXELS_MAX).rev().find(|&p| fits(p, 1)) {
+            Some(pixels) => (pixels, 1),
+            None => {
+                let cells = (1..=CELLS_MAX.ilog2())
+                    .map(|k| 1 << k)
+                    .find(|&c| fits(1, c))
+                    .unwrap_or(CELLS_MAX);
+                (1, cells)
+            }
+        };
+
+        let center_x = bounds.x_min as f64 + cols as f64 / 2.0;
+        let center_y = bounds.y_min as f64 + rows as f64 / 2.0;
+        self.x = center_x - self.in_cells(width as i32) / 2.0;
+        self.y = center_y - self.in_cells(height as i32) / 2.0;
+    }
+
+    // Pixels per cell after zooming from the current ones to `pixels`, stopping
+    // at the starting ones when in between.
+    fn via_home(&self, pixels: u32) -> u32 {
+        let (low, high) = (pixels.min(self.pixels), pixels.max(self.pixels));
+        if low < self.home && self.home < high {
+            self.home
+        } else {
+            pixels
+        }
+    }
+
+    // Converts a length in pixels into a length in cells. Dividing last keeps
+    // whole results exact.
+    fn in_cells(&self, pixels: i32) -> f64 {
+        pixels as f64 * self.cells as f64 / self.pixels as f64
+    }
+
+    // Board coords under a pixel, not rounded to a cell.
+    fn board_coords(&self, pixel: Coords) -> (f64, f64) {
+        (
+            self.x + self.in_cells(pixel.x),
+            self.y + self.in_cells(pixel.y),
+        )
+    }
+
+    fn zoom_to(&mut self, anchor: Coords, pixels: u32, cells: u32) -> bool {
+        let (x, y) = self.board_coords(anchor);
+        self.pixels = pixels;
+        self.cells = cells;
+        self.x = x - self.in_cells(anchor.x);
+        self.y = y - self.in_cells(anchor.y);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coords(x: i32, y: i32) -> Coords {
+        Coords { x, y }
+    }
+
+    #[test]
+    fn maps_pixels_to_cells() {
+        let mut camera = Camera::new(10);
+        assert_eq!(camera.screen_to_cell(coords(25, 9)), coords(2, 0));
+        assert_eq!(camera.cell_to_screen(coords(2, 0)), coords(20, 0));
+
+        camera.pan(-15, 30);
+        assert_eq!(camera.screen_to_cell(coords(0, 0)), coords(-2, 3));
+        assert_eq!(camera.cell_to_screen(coords(0, 3)), coords(15, 0));
+        assert_eq!(
+            camera.visible(40, 20),
+            Bounds {
+                x_min: -2,
+                x_max: 2,
+                y_min: 3,
+                y_max: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn zooms_around_anchor() {
+        let mut camera = Camera::new(10);
+        let anchor = coords(55, 35);
+        let cell = camera.screen_to_cell(anchor);
+
+        let levels: Vec<_> = std::iter::from_fn(|| {
+            camera
+                .zoom_out(anchor)
+                .then(|| (camera.pixels_per_cell(), camera.cells_per_pixel()))
+        })
+        .collect();
+        assert_eq!(
+            levels,
+            vec![(5, 1), (2, 1), (1, 1), (1, 2), (1, 4), (1, 8), (1, 16)]
+        );
+        assert_eq!(camera.screen_to_cell(anchor), coords(5, 3));
+
+        while camera.pixels_per_cell() < 10 {
+            camera.zoom_in(anchor);
+        }
+        assert_eq!(camera.pixels_per_cell(), 10);
+        assert_eq!(camera.screen_to_cell(anchor), cell);
+
+        let levels: Vec<_> =
+            std::iter::from_fn(|| camera.zoom_in(anchor).then(|| camera.pixels_per_cell()))
+                .collect();
+        assert_eq!(levels, vec![20, 40, 64]);
+    }
+
+    #[test]
+    fn fits_bounds() {
+        let mut camera = Camera::new(10);
+        let bounds = |x_max, y_max| Bounds {
+            x_min: 100,
+            x_max,
+            y_min: -10,
+            y_max,
+        };
+
+        camera.fit(bounds(109, -1), 200, 100);
+        assert_eq!(camera.pixels_per_cell(), 10);
+        assert_eq!(camera.cell_to_screen(coords(100, -10)), coords(50, 0));
+
+        camera.fit(bounds(899, 189), 200, 100);
+        assert_eq!(camera.cells_per_pixel(), 4);
+        assert_eq!(camera.screen_to_cell(coords(100, 50)), coords(500, 90));
+    }
+}
@@ -1,4 +1,9 @@
-use std::str::{FromStr, SplitWhitespace};
+use std::{
+    path::PathBuf,
+    str::{FromStr, SplitWhitespace},
+};
+
+use super::register::{PasteMode, Registers, UNNAMED};
 
 #[derive(Debug)]
 pub enum Command {
@@ -6,8 +11,47 @@ pub enum Command {
     BoardClear,
     // Move cursor to coordinates. If no cursor is active, creates one.
     Cursor(i32, i32),
+    // Cuts the selected cells into a register.
+    Cut(char),
+    // Replaces the board with the pattern file, centred.
+    Edit(PathBuf),
+    // Copies a register to the system clipboard, as RLE.
+    Export(char),
+    // Mirrors the selected cells horizontally (x) or vertically (y).
+    Flip(Axis),
+    // Replaces a register with the pattern found in the system clipboard.
+    Import(char),
+    // Inserts a pattern of the library, centred on the cursor.
+    Insert(String),
+    // Shows or hides the list of the patterns of the library.
+    Library,
+    // Previews a register at the cursor, optionally changing the paste mode.
+    Paste(char, Option<PasteMode>),
     // Gracefully terminate the application.
     Quit,
+    // Inserts the pattern file at the cursor, over the existing cells.
+    Read(PathBuf),
+    // Steps the simulation back by the given number of steps, 1 by default.
+    Rewind(usize),
+    // Runs the simulation until the given generation, then pauses it.
+    RunUntil(u64),
+    // Runs the selected cells on their own to find whether they form a
+    // spaceship, reporting its period, displacement and speed.
+    Ship,
+    // Runs the simulation for the given number of generations, then pauses it.
+    Step(u64),
+    // Swaps the rows and columns of the selected cells.
+    Transpose,
+    // Writes the board to a pattern file, in the format of its extension.
+    Write(PathBuf),
+    // Copies the selected cells into a register.
+    Yank(char),
+}
+
+#[derive(Debug)]
+pub enum Axis {
+    X,
+    Y,
 }
 
 #[derive(Debug)]
@@ -30,11 +74,77 @@ pub fn parse(input: &str) -> Result<Command, ParseError> {
             let y = parse_numeric(parts.next())?;
             Ok(Command::Cursor(x, y))
         }
+        "cut" => Ok(Command::Cut(parse_register(parts.next())?)),
+        "e" | "edit" => Ok(Command::Edit(parse_path(parts)?)),
+        "export" => Ok(Command::Export(parse_register(parts.next())?)),
+        "flip" => match parts.next() {
+            Some("x") => Ok(Command::Flip(Axis::X)),
+            Some("y") => Ok(Command::Flip(Axis::Y)),
+            Some(_) => Err(ParseError::InvalidArgument),
+            None => Err(ParseError::MissingArgument),
+        },
+        "import" => Ok(Command::Import(parse_register(parts.next())?)),
+        "insert" => match parts.next() {
+            Some(name) => Ok(Command::Insert(name.to_string())),
+            None => Err(ParseError::MissingArgument),
+        },
+        "lib" | "library" => Ok(Command::Library),
+        "p" | "paste" => parse_paste_command(parts),
         "q" | "quit" => Ok(Command::Quit),
+        "r" | "read" => Ok(Command::Read(parse_path(parts)?)),
+        "rew" | "rewind" => match parts.next() {
+            Some(n) => Ok(Command::Rewind(parse_numeric(Some(n))?)),
+            None => Ok(Command::Rewind(1)),
+        },
+        "run" => parse_run_command(parts),
+        "ship" => Ok(Command::Ship),
+        "step" => match parts.next() {
+            Some(n) => Ok(Command::Step(parse_numeric(Some(n))?)),
+            None => Ok(Command::Step(1)),
+        },
+        "transpose" => Ok(Command::Transpose),
+        "w" | "write" => Ok(Command::Write(parse_path(parts)?)),
+        "y" | "yank" => Ok(Command::Yank(parse_register(parts.next())?)),
         _ => Err(ParseError::InvalidCommand),
     }
 }
 
+// Paths take the rest of the input, so they may contain spaces.
+fn parse_path(parts: SplitWhitespace) -> Result<PathBuf, ParseError> {
+    let path = parts.collect::<Vec<_>>().join(" ");
+    if path.is_empty() {
+        Err(ParseError::MissingArgument)
+    } else {
+        Ok(PathBuf::from(path))
+    }
+}
+
+// Registers are single characters, the unnamed one by default.
+fn parse_register(value: Option<&str>) -> Result<char, ParseError> {
+    let Some(value) = value else {
+        return Ok(UNNAMED);
+    };
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(name), None) if Registers::is_valid_name(name) => Ok(name),
+        _ => Err(ParseError::InvalidArgument),
+    }
+}
+
+// The register and the paste mode are both optional, in any order.
+fn parse_paste_command(parts: SplitWhitespace) -> Result<Command, ParseError> {
+    let mut register = UNNAMED;
+    let mut mode = None;
+    for part in parts {
+        if part.chars().count() == 1 {
+            register = parse_register(Some(part))?;
+        } else {
+            mode = Some(part.parse().map_err(|_| ParseError::InvalidArgument)?);
+        }
+    }
+    Ok(Command::Paste(register, mode))
+}
+
 fn parse_board_command(mut parts: SplitWhitespace) -> Result<Command, ParseError> {
     match parts.next().unwrap_or_default() {
         "clear" => Ok(Command::BoardClear),
@@ -42,6 +152,14 @@ fn parse_board_command(mut parts: SplitWhitespace) -> Result<Command, ParseError
     }
 }
 
+fn parse_run_command(mut parts: SplitWhitespace) -> Result<Command, ParseError> {
+    match parts.next() {
+        Some("until") => Ok(Command::RunUntil(parse_numeric(parts.next())?)),
+        Some(_) => Err(ParseError::InvalidCommand),
+        None => Err(ParseError::MissingArgument),
+    }
+}
+
 // fn parse_cursor_command(mut parts: SplitWhitespace) -> Result<Command, ParseError> {
 //     match parts.next().unwrap_or_default() {
 //         "clear" => Ok(Command::BoardClear),
@@ -73,3 +191,26 @@ impl Numeric for u32 {}
 impl Numeric for u64 {}
 impl Numeric for u128 {}
 impl Numeric for usize {}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn parses_file_commands() {
+        let path = |input| match parse(input) {
+            Ok(Command::Edit(path) | Command::Read(path) | Command::Write(path)) => Some(path),
+            _ => None,
+        };
+        assert!(matches!(parse(":e glider.rle"), Ok(Command::Edit(_))));
+        assert!(matches!(parse(":read glider.rle"), Ok(Command::Read(_))));
+        assert!(matches!(parse(":w glider.rle"), Ok(Command::Write(_))));
+        assert_eq!(
+            path(":r my patterns/gun.rle").as_deref(),
+            Some(Path::new("my patterns/gun.rle"))
+        );
+        assert!(matches!(parse(":w"), Err(ParseError::MissingArgument)));
+    }
+}
@@ -15,8 +15,29 @@
 //! -       `Space` - start/stop simulation
 //! -           `+` - increase simulation speed
 //! -           `-` - decrease simulation speed
+//! -           `>` - enter/speed up hyperspeed, doubling generations per step
+//! -           `<` - slow down/leave hyperspeed, halving generations per step
 //! -           `'` - toggle grid
+//! -           `.` - advance one generation while paused
+//! -           `,` - rewind one step, pausing the simulation
+//! -           `u` - undo last edit
+//! -    `Ctrl` + `r` - redo last undone edit
 //! -           `:` - enter Command mode
+//! -           `v` - switch selection tool between rectangle and line
+//! -    `Ctrl` + `a` - select every live cell
+//! -           `]` - zoom in
+//! -           `[` - zoom out
+//! -           `z` - zoom to fit the live cells
+//! -  `Shift` + `left` | `h` - pan view left
+//! -  `Shift` + `down` | `j` - pan view down
+//! -    `Shift` + `up` | `k` - pan view up
+//! - `Shift` + `right` | `l` - pan view right
+//!
+//! When selection is not active:
+//! -  `left` | `h` - pan view left
+//! -  `down` | `j` - pan view down
+//! -    `up` | `k` - pan view up
+//! - `right` | `l` - pan view right
 //!
 //! When selection is active:
 //! -  `left` | `h` - move selection left
@@ -25,13 +46,25 @@
 //! - `right` | `l` - move selection right
 //! -           `r` - rotate selection clockwise
 //! -           `R` - rotate selection counter-clockwise
+//! -           `f` - mirror selected cells horizontally
+//! -           `F` - mirror selected cells vertically
+//! -           `\` - transpose selected cells
 //! -           `T` - toggle cells (dead->alive/alive->dead)
-//! -           `x` - clear selection
+//! -           `x` - clear selection, cancelling any paste
+//! -           `y` - yank selected cells into the unnamed register
+//! -           `d` - cut selected cells into the unnamed register
+//! -           `p` - preview the unnamed register at the selection, or paste
+//! -                 the previewed register
 //!
 //! ## `Normal` mode mouse bindings
 //!
 //! -     LMB Click - toggle cell (dead->alive/alive->dead)
 //! - S + LMB Click - toggle select cell, effectively creating selection
+//! -      RMB Drag - select a rectangle or a line, see `selection` for the
+//!                   `Shift`, `Ctrl` and `Alt` modifiers
+//! - RMB Dbl Click - select the object under the mouse
+//! -      MMB Drag - pan view
+//! -         Wheel - zoom in/out around the mouse
 //!
 //! When selection is active:
 //!
@@ -49,7 +82,10 @@ use sdl2::{
     mouse::MouseButton,
 };
 
-use crate::core::Coords;
+use crate::{core::Coords, render::sdl::selection::SelectMode};
+
+// Pixels the view is panned by on each key press.
+const PAN_STEP: i32 = 32;
 
 enum PollResult {
     Continue,
@@ -74,27 +110,53 @@ pub enum Action {
     ExecCommand,
 
     // Normal
+    CamFit,
+    CamPan(i32, i32),          // pan view by pixels
+    CamZoomIn(Option<Coords>), // zoom around pixel, board center by default
+    CamZoomOut(Option<Coords>),
+    SelAll,
     SelClear,
+    SelCut,
+    SelDragEnd(SelectMode),
+    SelDragMove(Coords),
+    SelDragStart(Coords),
+    SelFlipX,
+    SelFlipY,
     SelLRot,
     SelMoveDown,
     SelMoveLeft,
     SelMoveRight,
     SelMoveUp,
+    SelObject(Coords, SelectMode),
+    SelPaste,
     SelRRot,
     SelReCenter(Coords),
     SelToggle(Coords), // toggle selection
     SelToggleCell,     // toggle selected cell value
+    SelToolToggle,
+    SelTranspose,
+    SelYank,
     SimGridToggle,
+    SimHyperDecr,
+    SimHyperIncr,
+    SimRedo,
+    SimRewind,
     SimSpeedDecr,
     SimSpeedIncr,
     SimStartStop,
+    SimStep,
     SimToggleCell(Coords), // toggle clicked cell value
+    SimUndo,
 }
 
 pub struct EventHandler {
     pub mode: Mode,
     event_pump: EventPump,
     pub(crate) game_has_sel: bool,
+    // Set while the right mouse button is held down.
+    dragging: bool,
+    // Set while the middle mouse button is held down.
+    panning: bool,
 }
 
 impl EventHandler {
@@ -103,6 +165,8 @@ impl EventHandler {
             event_pump,
             mode: Mode::Normal,
             game_has_sel: false,
+            dragging: false,
+            panning: false,
         }
     }
 
@@ -152,7 +216,7 @@ impl EventHandler {
     /// using Keycode alone and I had to use the associated plain key, eg. `=`,
     /// detecting `Shift` keypress. I'll investigate this further, checking
     /// whether using scancodes works better.
-    fn handle_event_normal(&self, event: Event, actions: &mut Vec<Action>) -> PollResult {
+    fn handle_event_normal(&mut self, event: Event, actions: &mut Vec<Action>) -> PollResult {
         match event {
             Event::KeyDown {
                 keycode: Some(keycode),
@@ -166,12 +230,41 @@ impl EventHandler {
                     actions.push(Action::SimSpeedIncr)
                 }
                 (Keycode::Minus, _) => actions.push(Action::SimSpeedDecr),
+                (Keycode::Period, Mod::LSHIFTMOD | Mod::RSHIFTMOD) => {
+                    // (Shift.) -> >
+                    actions.push(Action::SimHyperIncr)
+                }
+                (Keycode::Comma, Mod::LSHIFTMOD | Mod::RSHIFTMOD) => {
+                    // (Shift,) -> <
+                    actions.push(Action::SimHyperDecr)
+                }
+                (Keycode::Period, _) => actions.push(Action::SimStep),
+                (Keycode::Comma, _) => actions.push(Action::SimRewind),
                 (Keycode::Quote, _) => actions.push(Action::SimGridToggle),
+                (Keycode::A, Mod::LCTRLMOD | Mod::RCTRLMOD) => actions.push(Action::SelAll),
+                (Keycode::R, Mod::LCTRLMOD | Mod::RCTRLMOD) => actions.push(Action::SimRedo),
+                (Keycode::U, _) => actions.push(Action::SimUndo),
+                (Keycode::V, _) => actions.push(Action::SelToolToggle),
+                (Keycode::RightBracket, _) => actions.push(Action::CamZoomIn(None)),
+                (Keycode::LeftBracket, _) => actions.push(Action::CamZoomOut(None)),
+                (Keycode::Z, _) => actions.push(Action::CamFit),
                 (Keycode::Semicolon, Mod::LSHIFTMOD | Mod::RSHIFTMOD) => {
                     // (Shift;) -> :
                     actions.push(Action::AppendCommandChar(":".to_string()));
                     actions.push(Action::SwitchMode(Mode::Command));
                 }
+                (Keycode::H | Keycode::Left, Mod::LSHIFTMOD | Mod::RSHIFTMOD) => {
+                    actions.push(Action::CamPan(-PAN_STEP, 0))
+                }
+                (Keycode::J | Keycode::Down, Mod::LSHIFTMOD | Mod::RSHIFTMOD) => {
+                    actions.push(Action::CamPan(0, PAN_STEP))
+                }
+                (Keycode::K | Keycode::Up, Mod::LSHIFTMOD | Mod::RSHIFTMOD) => {
+                    actions.push(Action::CamPan(0, -PAN_STEP))
+                }
+                (Keycode::L | Keycode::Right, Mod::LSHIFTMOD | Mod::RSHIFTMOD) => {
+                    actions.push(Action::CamPan(PAN_STEP, 0))
+                }
 
                 //
                 // Active Selection
@@ -192,16 +285,36 @@ impl EventHandler {
                     actions.push(Action::SelLRot)
                 }
                 (Keycode::R, _) if self.game_has_sel => actions.push(Action::SelRRot),
+                (Keycode::F, Mod::LSHIFTMOD | Mod::RSHIFTMOD) if self.game_has_sel => {
+                    actions.push(Action::SelFlipY)
+                }
+                (Keycode::F, _) if self.game_has_sel => actions.push(Action::SelFlipX),
+                (Keycode::Backslash, _) if self.game_has_sel => actions.push(Action::SelTranspose),
                 (Keycode::T, Mod::LSHIFTMOD | Mod::RSHIFTMOD) if self.game_has_sel => {
                     actions.push(Action::SelToggleCell)
                 }
                 (Keycode::X, _) if self.game_has_sel => actions.push(Action::SelClear),
+                (Keycode::Y, _) if self.game_has_sel => actions.push(Action::SelYank),
+                (Keycode::D, _) if self.game_has_sel => actions.push(Action::SelCut),
+                (Keycode::P, _) if self.game_has_sel => actions.push(Action::SelPaste),
+
+                //
+                // No Selection
+                //
+                (Keycode::H | Keycode::Left, _) => actions.push(Action::CamPan(-PAN_STEP, 0)),
+                (Keycode::J | Keycode::Down, _) => actions.push(Action::CamPan(0, PAN_STEP)),
+                (Keycode::K | Keycode::Up, _) => actions.push(Action::CamPan(0, -PAN_STEP)),
+                (Keycode::L | Keycode::Right, _) => actions.push(Action::CamPan(PAN_STEP, 0)),
 
                 _ => {}
             },
 
             Event::MouseButtonDown {
-                mouse_btn, x, y, ..
+                mouse_btn,
+                clicks,
+                x,
+                y,
+                ..
             } => match mouse_btn {
                 MouseButton::Left if self.is_shift_pressed() => {
                     actions.push(Action::SelToggle(Coords { x, y }));
@@ -212,10 +325,57 @@ impl EventHandler {
                 MouseButton::Left => {
                     actions.push(Action::SimToggleCell(Coords { x, y }));
                 }
+                MouseButton::Right if clicks >= 2 => {
+                    actions.push(Action::SelObject(Coords { x, y }, self.select_mode()));
+                }
+                MouseButton::Right => {
+                    self.dragging = true;
+                    actions.push(Action::SelDragStart(Coords { x, y }));
+                }
+                MouseButton::Middle => self.panning = true,
 
                 _ => {}
             },
 
+            Event::MouseMotion { x, y, .. } if self.dragging => {
+                actions.push(Action::SelDragMove(Coords { x, y }));
+            }
+
+            // the board follows the mouse
+            Event::MouseMotion { xrel, yrel, .. } if self.panning => {
+                actions.push(Action::CamPan(-xrel, -yrel));
+            }
+
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Middle,
+                ..
+            } => self.panning = false,
+
+            Event::MouseWheel {
+                y,
+                mouse_x,
+                mouse_y,
+                ..
+            } => {
+                let anchor = Some(Coords {
+                    x: mouse_x,
+                    y: mouse_y,
+                });
+                match y.signum() {
+                    1 => actions.push(Action::CamZoomIn(anchor)),
+                    -1 => actions.push(Action::CamZoomOut(anchor)),
+                    _ => {}
+                }
+            }
+
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Right,
+                ..
+            } if self.dragging => {
+                self.dragging = false;
+                actions.push(Action::SelDragEnd(self.select_mode()));
+            }
+
             Event::Quit { .. } => return PollResult::Quit,
 
             _ => {}
@@ -223,6 +383,24 @@ impl EventHandler {
         PollResult::Continue
     }
 
+    /// Returns how a new selection is combined with the existing one, from the
+    /// modifier keys held down.
+    fn select_mode(&self) -> SelectMode {
+        let kbd_state = self.event_pump.keyboard_state();
+        let is_pressed = |left, right| {
+            kbd_state.is_scancode_pressed(left) || kbd_state.is_scancode_pressed(right)
+        };
+        if is_pressed(Scancode::LShift, Scancode::RShift) {
+            SelectMode::Add
+        } else if is_pressed(Scancode::LCtrl, Scancode::RCtrl) {
+            SelectMode::Subtract
+        } else if is_pressed(Scancode::LAlt, Scancode::RAlt) {
+            SelectMode::Intersect
+        } else {
+            SelectMode::Replace
+        }
+    }
+
     /// Returns true if either left or right shift keys are pressed.
     ///
     /// This method checks event pump's keyboard state, and it is necessary when
@@ -1,27 +1,71 @@
-use crate::{core::Coords, render::sdl::selection::Selection};
+use std::ops::Range;
+
+use crate::{
+    core::{Coords, Cycle},
+    render::sdl::{
+        camera::Camera,
+        register::{PasteMode, Registers},
+        selection::{SelectTool, Selection},
+    },
+};
 
 const SIM_PERIOD_STEP: u64 = 33;
 const SIM_PERIOD_MAX: u64 = 33;
 const SIM_PERIOD_MIN: u64 = 330;
+const HYPERSPEED_MAX: u8 = 48;
 
 pub struct GameState {
+    // Part of the board shown in the window.
+    pub(crate) camera: Camera,
     pub(crate) command: Option<String>,
+    // Set once the board settled into a still life or an oscillator.
+    pub(crate) cycle: Option<Cycle>,
+    // Corners of the shape being dragged, in board coords.
+    pub(crate) drag: Option<(Coords, Coords)>,
+    // The simulation pauses once this generation is reached.
+    pub(crate) generation_limit: Option<u64>,
+    // When set, each simulation step leaps `2^k` generations using HashLife.
+    pub(crate) hyperspeed: Option<u8>,
+    // Feedback of the last command, shown in the command line.
+    pub(crate) message: Option<String>,
+    // Register previewed at the cursor, until it is pasted.
+    pub(crate) paste: Option<char>,
+    pub(crate) paste_mode: PasteMode,
+    pub(crate) registers: Registers,
     pub(crate) running: bool,
+    pub(crate) select_tool: SelectTool,
     pub(crate) selection: Selection,
     pub(crate) show_grid: bool,
     pub(crate) show_help: bool,
+    // Lists the patterns of the library over the board.
+    pub(crate) show_library: bool,
     pub(crate) sim_period_ms: u64,
+    // Generations run by `:step` or `:run until`, the simulation pauses at
+    // the end of the range.
+    pub(crate) target: Option<Range<u64>>,
 }
 
 impl Default for GameState {
     fn default() -> Self {
         Self {
+            camera: Camera::new(1),
             command: None,
+            cycle: None,
+            drag: None,
+            generation_limit: None,
+            hyperspeed: None,
+            message: None,
+            paste: None,
+            paste_mode: PasteMode::default(),
+            registers: Registers::default(),
+            select_tool: SelectTool::default(),
             selection: Selection::default(),
             running: false,
             show_grid: true,
             show_help: false,
+            show_library: false,
             sim_period_ms: 33,
+            target: None,
         }
     }
 }
@@ -48,6 +92,19 @@ impl GameState {
     pub fn sim_speed_incr(&mut self) {
         self.sim_period_ms = (self.sim_period_ms - SIM_PERIOD_STEP).max(SIM_PERIOD_MAX);
     }
+    /// Sets the speed in generations per second, 0 being the fastest.
+    pub fn set_sim_speed(&mut self, speed: u32) {
+        self.sim_period_ms = match speed {
+            0 => SIM_PERIOD_MAX,
+            speed => (1000 / speed as u64).clamp(SIM_PERIOD_MAX, SIM_PERIOD_MIN),
+        };
+    }
+    pub fn hyperspeed_decr(&mut self) {
+        self.hyperspeed = self.hyperspeed.and_then(|k| k.checked_sub(1));
+    }
+    pub fn hyperspeed_incr(&mut self) {
+        self.hyperspeed = Some(self.hyperspeed.map_or(0, |k| (k + 1).min(HYPERSPEED_MAX)));
+    }
     pub fn toggle_grid(&mut self) {
         self.show_grid = !self.show_grid;
     }
@@ -58,6 +115,7 @@ impl GameState {
     }
     pub fn clear_sel(&mut self) {
         self.selection.clear();
+        self.paste = None;
     }
     pub fn has_sel(&self) -> bool {
         !self.selection.is_empty()
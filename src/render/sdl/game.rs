@@ -1,9 +1,21 @@
+use std::{mem, path::Path};
+
+use sdl2::clipboard::ClipboardUtil;
+
 use crate::{
-    core::State,
+    core::{
+        Bounds, Cell, Coords, CycleDetector, HashLife, Simulation, SparseState,
+        format::{self, Pattern, rle},
+        library,
+    },
     render::sdl::{
-        command::{Command, parse},
+        camera::Camera,
+        command::{Axis, Command, parse},
         event_handler::Mode,
         game_state::GameState,
+        history::{History, Snapshot},
+        register::{self, UNNAMED},
+        selection::{self, SelectMode, SelectTool, Selection},
     },
 };
 
@@ -13,30 +25,59 @@ use super::{
     timer::Timer,
 };
 
-pub struct Game<'a> {
+// Generations run by `:ship` before giving up on finding a cycle.
+const SHIP_GENERATIONS: u64 = 512;
+
+// Memory used by the undo and rewind history by default, in bytes.
+const HISTORY_LIMIT: usize = 64 << 20;
+
+/// Game drives any simulation engine, eg. the dense `State` or the unbounded
+/// `SparseState`.
+///
+/// In hyperspeed mode the cells are moved onto a HashLife universe, which is
+/// unbounded regardless of the engine topology, and they are copied back as
+/// soon as the board is edited or hyperspeed is left.
+///
+/// The board is recorded before every edit and before the first step of every
+/// frame, so that edits can be undone and generations rewound.
+pub struct Game<'a, S: Simulation> {
     actions: Vec<Action>,
+    clipboard: Option<ClipboardUtil>,
+    cycle_detector: CycleDetector,
     event_handler: EventHandler,
     game_state: GameState,
+    hashlife: Option<HashLife>,
+    history: History,
+    // Whether a generation was recorded since the frame started.
+    recorded: bool,
     renderer: Renderer<'a>,
-    state: State,
+    state: S,
     timer: Timer,
     //
     timer_acc_ms: u64,
 }
 
-impl<'a> Game<'a> {
+impl<'a, S: Simulation> Game<'a, S> {
     pub fn new(
         event_handler: EventHandler,
         renderer: Renderer<'a>,
         timer: Timer,
-        state: State,
+        state: S,
     ) -> Self {
-        let game_state = GameState::default();
+        let game_state = GameState {
+            camera: Camera::new(renderer.layout.scale),
+            ..GameState::default()
+        };
 
         Game {
             actions: Vec::new(),
+            clipboard: None,
+            cycle_detector: CycleDetector::default(),
             event_handler,
             game_state,
+            hashlife: None,
+            history: History::new(HISTORY_LIMIT),
+            recorded: false,
             renderer,
             state,
             timer,
@@ -44,11 +85,35 @@ impl<'a> Game<'a> {
         }
     }
 
+    pub fn with_speed(mut self, speed: u32) -> Self {
+        self.game_state.set_sim_speed(speed);
+        self
+    }
+
+    pub fn with_generation_limit(mut self, limit: Option<u64>) -> Self {
+        self.game_state.generation_limit = limit;
+        self
+    }
+
+    /// Enables `:export` and `:import` of registers through the system
+    /// clipboard.
+    pub fn with_clipboard(mut self, clipboard: ClipboardUtil) -> Self {
+        self.clipboard = Some(clipboard);
+        self
+    }
+
+    /// Caps the memory used by the undo and rewind history, in bytes.
+    pub fn with_history_limit(mut self, limit: usize) -> Self {
+        self.history = History::new(limit);
+        self
+    }
+
     pub fn run(&mut self) -> Result<(), String> {
         'running: loop {
             self.timer.start();
 
             self.timer_acc_ms += self.timer.frame_duration;
+            self.recorded = false;
 
             self.actions.clear();
             if let Action::Quit = self.event_handler.poll(&mut self.actions) {
@@ -61,12 +126,24 @@ impl<'a> Game<'a> {
 
             while self.timer_acc_ms >= self.game_state.sim_period_ms {
                 if self.game_state.running {
-                    self.state.next();
+                    self.step();
+                    if self.has_reached_target() {
+                        self.game_state.target = None;
+                        self.game_state.pause();
+                    }
+                    if self.has_reached_limit() {
+                        self.game_state.pause();
+                    }
                 }
                 self.timer_acc_ms -= self.game_state.sim_period_ms;
             }
 
-            self.renderer.draw(&self.state, &self.game_state)?;
+            let state: &dyn Simulation = match &self.hashlife {
+                Some(hashlife) => hashlife,
+                None => &self.state,
+            };
+            self.game_state.cycle = self.cycle_detector.cycle();
+            self.renderer.draw(state, &self.game_state)?;
 
             self.timer.delay_if_early();
         }
@@ -74,37 +151,267 @@ impl<'a> Game<'a> {
         Ok(())
     }
 
+    /// Advance the simulation by one generation, or by `2^k` generations when
+    /// hyperspeed is on. Rules that HashLife does not support turn hyperspeed
+    /// off.
+    ///
+    /// Leaps are shortened so as not to overshoot the target generation or the
+    /// generation limit. Cycles are only looked for one generation at a time,
+    /// the detection starting over once hyperspeed is left. Only the board
+    /// before the first step of a frame is recorded.
+    fn step(&mut self) {
+        if !self.recorded && self.history.fits(self.population()) {
+            let snapshot = self.snapshot();
+            self.history.record_generation(snapshot);
+        }
+        self.recorded = true;
+
+        let Some(k) = self.game_state.hyperspeed else {
+            self.step_cells();
+            return;
+        };
+        let generation = self.generation();
+        let k = [
+            self.game_state.target.as_ref().map(|target| target.end),
+            self.game_state.generation_limit,
+        ]
+        .into_iter()
+        .flatten()
+        .map(|end| end.saturating_sub(generation).max(1).ilog2() as u8)
+        .fold(k, u8::min);
+
+        if self.hashlife.is_none() {
+            match HashLife::from_simulation(&self.state) {
+                Ok(hashlife) => self.hashlife = Some(hashlife),
+                Err(_) => {
+                    self.game_state.hyperspeed = None;
+                    self.step_cells();
+                    return;
+                }
+            }
+        }
+        if let Some(hashlife) = &mut self.hashlife {
+            hashlife.step_pow2(k);
+        }
+    }
+
+    /// Advances exactly one generation, even in hyperspeed.
+    fn step_once(&mut self) {
+        Self::sync_hashlife(&mut self.state, &mut self.hashlife);
+        if self.history.fits(self.state.population()) {
+            self.history.record_generation(Snapshot::take(&self.state));
+        }
+        self.step_cells();
+    }
+
+    /// Steps the simulation by one generation, observing both ends of the step
+    /// so that a board edited since the last one is not missed.
+    fn step_cells(&mut self) {
+        self.cycle_detector.observe(&self.state);
+        self.state.step();
+        self.cycle_detector.observe(&self.state);
+    }
+
+    /// Runs the simulation until the given generation, pausing there.
+    fn run_until(&mut self, end: u64) {
+        let generation = self.generation();
+        if end <= generation {
+            self.game_state.message = Some(format!("generation {end} already reached"));
+            return;
+        }
+        self.game_state.target = Some(generation..end);
+        self.game_state.running = true;
+    }
+
+    fn generation(&self) -> u64 {
+        match &self.hashlife {
+            Some(hashlife) => hashlife.generation(),
+            None => self.state.generation(),
+        }
+    }
+
+    fn population(&self) -> usize {
+        match &self.hashlife {
+            Some(hashlife) => hashlife.population(),
+            None => self.state.population(),
+        }
+    }
+
+    fn has_reached_target(&self) -> bool {
+        let generation = self.generation();
+        self.game_state
+            .target
+            .as_ref()
+            .is_some_and(|target| generation >= target.end)
+    }
+
+    fn has_reached_limit(&self) -> bool {
+        let generation = self.generation();
+        self.game_state
+            .generation_limit
+            .is_some_and(|limit| generation >= limit)
+    }
+
+    /// Records the board as currently displayed.
+    fn snapshot(&self) -> Snapshot {
+        match &self.hashlife {
+            Some(hashlife) => Snapshot::take(hashlife),
+            None => Snapshot::take(&self.state),
+        }
+    }
+
+    /// Replaces the board with a recorded one, leaving hyperspeed's universe
+    /// behind.
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.hashlife = None;
+        snapshot.restore(&mut self.state);
+        self.cycle_detector.reset();
+    }
+
+    /// Steps the simulation `n` steps back, which can be undone. The game is
+    /// paused, so that the rewound generations can be inspected.
+    fn rewind(&mut self, n: usize) {
+        let current = self.snapshot();
+        match self.history.rewind(n) {
+            Some(snapshot) => {
+                self.history.record_edit(current);
+                self.restore(snapshot);
+                self.game_state.pause();
+            }
+            None => self.game_state.message = Some("no generation to rewind".to_string()),
+        }
+    }
+
+    fn undo(&mut self) {
+        match self.history.undo(self.snapshot()) {
+            Some(snapshot) => self.restore(snapshot),
+            None => self.game_state.message = Some("nothing to undo".to_string()),
+        }
+    }
+
+    fn redo(&mut self) {
+        match self.history.redo(self.snapshot()) {
+            Some(snapshot) => self.restore(snapshot),
+            None => self.game_state.message = Some("nothing to redo".to_string()),
+        }
+    }
+
+    /// Copies the cells computed in hyperspeed back into the simulation, which
+    /// wraps them onto its board.
+    fn sync_hashlife(state: &mut S, hashlife: &mut Option<HashLife>) {
+        if let Some(hashlife) = hashlife.take() {
+            state.clear();
+            for (coords, cell) in hashlife.live_cells() {
+                state.set_cell(coords.x, coords.y, cell);
+            }
+            state.set_generation(hashlife.generation());
+        }
+    }
+
     fn update(&mut self) -> Action {
-        for action in &self.actions {
+        // taken out of the game, so that actions can mutably borrow it
+        let actions = mem::take(&mut self.actions);
+        for action in &actions {
             match action {
                 Action::Quit => unreachable!("Action quit should be handled on poll."),
                 Action::Continue => {}
                 // Normal mode actions
+                Action::CamFit => self.fit_view(),
+                Action::CamPan(dx, dy) => self.game_state.camera.pan(*dx, *dy),
+                Action::CamZoomIn(anchor) => {
+                    let anchor = anchor.unwrap_or_else(|| self.board_center());
+                    self.game_state.camera.zoom_in(anchor);
+                }
+                Action::CamZoomOut(anchor) => {
+                    let anchor = anchor.unwrap_or_else(|| self.board_center());
+                    self.game_state.camera.zoom_out(anchor);
+                }
+                Action::SelAll => {
+                    Self::sync_hashlife(&mut self.state, &mut self.hashlife);
+                    let live = selection::live(&self.state);
+                    self.game_state.selection.apply(live, SelectMode::Replace);
+                }
                 Action::SelClear => self.game_state.clear_sel(),
+                Action::SelCut => self.game_state.message = Some(self.cut(UNNAMED)),
+                Action::SelDragEnd(mode) => {
+                    if let Some((from, to)) = self.game_state.drag.take() {
+                        let shape = self.game_state.select_tool.shape(from, to);
+                        self.game_state.selection.apply(shape, *mode);
+                    }
+                }
+                Action::SelDragMove(coords) => {
+                    let to = self.cell_at(coords);
+                    if let Some((_, drag_to)) = &mut self.game_state.drag {
+                        *drag_to = to;
+                    }
+                }
+                Action::SelDragStart(coords) => {
+                    let from = self.cell_at(coords);
+                    self.game_state.drag = Some((from, from));
+                }
+                Action::SelFlipX => self.mirror_sel(Selection::flip_x),
+                Action::SelFlipY => self.mirror_sel(Selection::flip_y),
                 Action::SelLRot => self.game_state.rot_sel_counter(),
                 Action::SelMoveDown => self.game_state.mv_sel_down(1),
                 Action::SelMoveLeft => self.game_state.mv_sel_left(1),
                 Action::SelMoveRight => self.game_state.mv_sel_right(1),
                 Action::SelMoveUp => self.game_state.mv_sel_up(1),
+                Action::SelObject(coords, mode) => {
+                    Self::sync_hashlife(&mut self.state, &mut self.hashlife);
+                    let start = self.cell_at(coords);
+                    let object = selection::connected(&self.state, start);
+                    self.game_state.selection.apply(object, *mode);
+                }
+                Action::SelPaste => match self.game_state.paste {
+                    Some(_) => self.paste(),
+                    None => self.preview_paste(UNNAMED),
+                },
                 Action::SelRRot => self.game_state.rot_sel_clockwise(),
-                Action::SelReCenter(coords) => self
-                    .game_state
-                    .recenter_sel(coords.rescale(self.renderer.layout.scale)),
-                Action::SelToggle(coords) => self
-                    .game_state
-                    .add_to_sel(coords.rescale(self.renderer.layout.scale)),
+                Action::SelReCenter(coords) => self.game_state.recenter_sel(self.cell_at(coords)),
+                Action::SelToggle(coords) => self.game_state.add_to_sel(self.cell_at(coords)),
                 Action::SelToggleCell => {
+                    Self::sync_hashlife(&mut self.state, &mut self.hashlife);
+                    self.cycle_detector.reset();
+                    self.history.record_edit(Snapshot::take(&self.state));
                     for coords in self.game_state.iter_sel() {
                         self.state.toggle_cell(coords);
                     }
                 }
+                Action::SelToolToggle => {
+                    let tool = match self.game_state.select_tool {
+                        SelectTool::Rect => SelectTool::Line,
+                        SelectTool::Line => SelectTool::Rect,
+                    };
+                    self.game_state.select_tool = tool;
+                    self.game_state.message = Some(format!("{} selection tool", tool.name()));
+                }
+                Action::SelTranspose => self.mirror_sel(Selection::transpose),
+                Action::SelYank => self.game_state.message = Some(self.yank(UNNAMED)),
                 Action::SimGridToggle => self.game_state.toggle_grid(),
+                Action::SimHyperDecr => {
+                    self.game_state.hyperspeed_decr();
+                    if self.game_state.hyperspeed.is_none() {
+                        Self::sync_hashlife(&mut self.state, &mut self.hashlife);
+                    }
+                }
+                Action::SimHyperIncr => self.game_state.hyperspeed_incr(),
+                Action::SimRedo => self.redo(),
+                Action::SimRewind => self.rewind(1),
                 Action::SimSpeedDecr => self.game_state.sim_speed_decr(),
                 Action::SimSpeedIncr => self.game_state.sim_speed_incr(),
                 Action::SimStartStop => self.game_state.toggle_running(),
-                Action::SimToggleCell(coords) => self
-                    .state
-                    .toggle_cell(&coords.rescale(self.renderer.layout.scale)),
+                Action::SimStep => {
+                    if !self.game_state.running {
+                        self.step_once();
+                    }
+                }
+                Action::SimToggleCell(coords) => {
+                    Self::sync_hashlife(&mut self.state, &mut self.hashlife);
+                    self.cycle_detector.reset();
+                    self.history.record_edit(Snapshot::take(&self.state));
+                    self.state.toggle_cell(&self.cell_at(coords))
+                }
+                Action::SimUndo => self.undo(),
                 // == TODO ==
                 Action::AppendCommandChar(c) => {
                     self.game_state.command.get_or_insert_default().push_str(c)
@@ -125,27 +432,299 @@ impl<'a> Game<'a> {
                     if let Some(input) = self.game_state.command.take() {
                         return match parse(&input) {
                             Ok(command) => self.execute_command(command),
-                            // TODO print error on statusbar
-                            Err(_) => Action::Continue,
+                            Err(_) => {
+                                self.game_state.message = Some(format!("invalid command {input}"));
+                                Action::Continue
+                            }
                         };
                     }
                 }
                 Action::SwitchMode(Mode::Normal) => self.event_handler.mode = Mode::Normal,
-                Action::SwitchMode(Mode::Command) => self.event_handler.mode = Mode::Command,
+                Action::SwitchMode(Mode::Command) => {
+                    self.game_state.message = None;
+                    self.event_handler.mode = Mode::Command
+                }
             }
         }
+        self.actions = actions;
 
         self.event_handler.game_has_sel = self.game_state.has_sel();
 
         Action::Continue
     }
 
+    /// Returns the board coords of the cell under a pixel of the window.
+    fn cell_at(&self, pixel: &Coords) -> Coords {
+        self.game_state.camera.screen_to_cell(*pixel)
+    }
+
+    /// Returns the pixel at the center of the board area of the window.
+    fn board_center(&self) -> Coords {
+        let board = self.renderer.layout.board;
+        Coords {
+            x: board.center().x(),
+            y: board.center().y(),
+        }
+    }
+
+    /// Zooms the view so that every live cell is visible.
+    fn fit_view(&mut self) {
+        let state: &dyn Simulation = match &self.hashlife {
+            Some(hashlife) => hashlife,
+            None => &self.state,
+        };
+        let Some(bounds) = Bounds::from_coords(state.live_cells().map(|(coords, _)| coords)) else {
+            self.game_state.message = Some("nothing to fit".to_string());
+            return;
+        };
+        let board = self.renderer.layout.board;
+        self.game_state
+            .camera
+            .fit(bounds, board.width(), board.height());
+    }
+
     fn execute_command(&mut self, command: Command) -> Action {
         match command {
-            Command::BoardClear => self.state.clear(),
+            Command::BoardClear => {
+                Self::sync_hashlife(&mut self.state, &mut self.hashlife);
+                self.history.record_edit(Snapshot::take(&self.state));
+                self.cycle_detector.reset();
+                self.state.clear()
+            }
             Command::Cursor(x, y) => self.game_state.add_to_sel((x, y)),
+            Command::Cut(register) => self.game_state.message = Some(self.cut(register)),
+            Command::Edit(path) => self.game_state.message = Some(self.edit_file(&path)),
+            Command::Export(register) => self.game_state.message = Some(self.export(register)),
+            Command::Flip(Axis::X) => self.mirror_sel(Selection::flip_x),
+            Command::Flip(Axis::Y) => self.mirror_sel(Selection::flip_y),
+            Command::Import(register) => self.game_state.message = Some(self.import(register)),
+            Command::Insert(name) => self.game_state.message = Some(self.insert(&name)),
+            Command::Library => self.game_state.show_library = !self.game_state.show_library,
+            Command::Paste(register, mode) => {
+                if let Some(mode) = mode {
+                    self.game_state.paste_mode = mode;
+                }
+                self.preview_paste(register);
+            }
             Command::Quit => return Action::Quit,
+            Command::Read(path) => self.game_state.message = Some(self.read_file(&path)),
+            Command::Rewind(n) => self.rewind(n),
+            Command::RunUntil(generation) => self.run_until(generation),
+            Command::Ship => self.game_state.message = Some(self.classify_selection()),
+            Command::Step(n) => self.run_until(self.generation().saturating_add(n)),
+            Command::Transpose => self.mirror_sel(Selection::transpose),
+            Command::Write(path) => self.game_state.message = Some(self.write_file(&path)),
+            Command::Yank(register) => self.game_state.message = Some(self.yank(register)),
         }
         Action::Continue
     }
+
+    /// Replaces the board with a pattern file, centred, restarting from
+    /// generation 0. The rule of the board is kept.
+    fn edit_file(&mut self, path: &Path) -> String {
+        let pattern = match format::load(path) {
+            Ok(pattern) => pattern,
+            Err(e) => return format!("cannot load {}: {e}", path.display()),
+        };
+        let layout = &self.renderer.layout;
+        let x = (layout.cols as i32 - pattern.width as i32) / 2;
+        let y = (layout.rows as i32 - pattern.height as i32) / 2;
+
+        Self::sync_hashlife(&mut self.state, &mut self.hashlife);
+        self.history.record_edit(Snapshot::take(&self.state));
+        self.cycle_detector.reset();
+        self.state.clear();
+        self.state.set_generation(0);
+        self.game_state.target = None;
+        pattern.place(&mut self.state, x, y);
+        self.loaded_message(path, &pattern)
+    }
+
+    /// Inserts a pattern file with its top-left corner at the cursor.
+    fn read_file(&mut self, path: &Path) -> String {
+        let Some(cursor) = self.game_state.selection.top_left() else {
+            return "no cursor to insert at".to_string();
+        };
+        let pattern = match format::load(path) {
+            Ok(pattern) => pattern,
+            Err(e) => return format!("cannot load {}: {e}", path.display()),
+        };
+
+        Self::sync_hashlife(&mut self.state, &mut self.hashlife);
+        self.history.record_edit(Snapshot::take(&self.state));
+        self.cycle_detector.reset();
+        pattern.place(&mut self.state, cursor.x, cursor.y);
+        self.loaded_message(path, &pattern)
+    }
+
+    /// Inserts a pattern of the library, centred on the cursor.
+    fn insert(&mut self, name: &str) -> String {
+        let Some(center) = self.game_state.selection.center() else {
+            return "no cursor to insert at".to_string();
+        };
+        let Some(pattern) = library::get(name) else {
+            return format!("unknown pattern {name}, see :lib");
+        };
+        let x = center.x - pattern.width as i32 / 2;
+        let y = center.y - pattern.height as i32 / 2;
+
+        Self::sync_hashlife(&mut self.state, &mut self.hashlife);
+        self.history.record_edit(Snapshot::take(&self.state));
+        self.cycle_detector.reset();
+        pattern.place(&mut self.state, x, y);
+        self.game_state.show_library = false;
+        format!("inserted {}", pattern.name.as_deref().unwrap_or(name))
+    }
+
+    fn loaded_message(&self, path: &Path, pattern: &Pattern) -> String {
+        let message = format!(
+            "loaded {} cells from {}",
+            pattern.cells.len(),
+            path.display()
+        );
+        match pattern.rule {
+            Some(rule) if rule != *self.state.rule() => format!("{message}, rule {rule} ignored"),
+            _ => message,
+        }
+    }
+
+    fn write_file(&mut self, path: &Path) -> String {
+        Self::sync_hashlife(&mut self.state, &mut self.hashlife);
+        let pattern = Pattern::from_simulation(&self.state);
+        match format::save(path, &pattern) {
+            Ok(()) => format!(
+                "written {} cells to {}",
+                pattern.cells.len(),
+                path.display()
+            ),
+            Err(e) => format!("cannot write {}: {e}", path.display()),
+        }
+    }
+
+    /// Mirrors the selection, moving the selected cells along with it.
+    fn mirror_sel(&mut self, mirror: fn(&mut Selection, &mut S)) {
+        if !self.game_state.has_sel() {
+            self.game_state.message = Some("nothing selected".to_string());
+            return;
+        }
+        Self::sync_hashlife(&mut self.state, &mut self.hashlife);
+        self.history.record_edit(Snapshot::take(&self.state));
+        self.cycle_detector.reset();
+        mirror(&mut self.game_state.selection, &mut self.state);
+    }
+
+    /// Copies the selected cells into a register.
+    fn yank(&mut self, register: char) -> String {
+        if !self.game_state.has_sel() {
+            return "nothing selected".to_string();
+        }
+        Self::sync_hashlife(&mut self.state, &mut self.hashlife);
+        let pattern = register::copy(&self.state, self.game_state.selection.iter());
+        let message = format!("{} cells yanked into {register}", pattern.cells.len());
+        self.game_state.registers.set(register, pattern);
+        message
+    }
+
+    /// Copies the selected cells into a register, then kills them.
+    fn cut(&mut self, register: char) -> String {
+        let message = self.yank(register);
+        if self.game_state.has_sel() {
+            self.history.record_edit(Snapshot::take(&self.state));
+            self.cycle_detector.reset();
+            for coords in self.game_state.selection.iter() {
+                self.state.set_cell(coords.x, coords.y, Cell::Dead);
+            }
+        }
+        message
+    }
+
+    /// Shows a register at the cursor, until it is pasted with `p`.
+    fn preview_paste(&mut self, register: char) {
+        self.game_state.message = if !self.game_state.has_sel() {
+            Some("no cursor to paste at".to_string())
+        } else if self.game_state.registers.get(register).is_none() {
+            Some(format!("register {register} is empty"))
+        } else {
+            self.game_state.paste = Some(register);
+            Some(format!(
+                "pasting {register} in {} mode",
+                self.game_state.paste_mode
+            ))
+        };
+    }
+
+    /// Pastes the previewed register at the cursor.
+    fn paste(&mut self) {
+        let Some(register) = self.game_state.paste.take() else {
+            return;
+        };
+        let pattern = self.game_state.registers.get(register);
+        let Some((pattern, origin)) = pattern.zip(self.game_state.selection.top_left()) else {
+            return;
+        };
+        Self::sync_hashlife(&mut self.state, &mut self.hashlife);
+        self.history.record_edit(Snapshot::take(&self.state));
+        self.cycle_detector.reset();
+        let mode = self.game_state.paste_mode;
+        mode.paste(pattern, &mut self.state, origin.x, origin.y);
+        self.game_state.message = None;
+    }
+
+    fn export(&mut self, register: char) -> String {
+        let Some(clipboard) = &self.clipboard else {
+            return "no clipboard".to_string();
+        };
+        let Some(pattern) = self.game_state.registers.get(register) else {
+            return format!("register {register} is empty");
+        };
+        match clipboard.set_clipboard_text(&rle::write(pattern)) {
+            Ok(()) => format!("register {register} exported to the clipboard"),
+            Err(e) => format!("cannot export to the clipboard: {e}"),
+        }
+    }
+
+    fn import(&mut self, register: char) -> String {
+        let Some(clipboard) = &self.clipboard else {
+            return "no clipboard".to_string();
+        };
+        let text = match clipboard.clipboard_text() {
+            Ok(text) => text,
+            Err(e) => return format!("cannot import from the clipboard: {e}"),
+        };
+        match format::parse(&text) {
+            Ok(pattern) => {
+                let message = format!("{} cells imported into {register}", pattern.cells.len());
+                self.game_state.registers.set(register, pattern);
+                message
+            }
+            Err(e) => format!("cannot import from the clipboard: {e}"),
+        }
+    }
+
+    /// Runs the live cells of the selection apart from the board, on an
+    /// unbounded engine, and describes the cycle they settle into.
+    fn classify_selection(&mut self) -> String {
+        Self::sync_hashlife(&mut self.state, &mut self.hashlife);
+        let mut sparse = SparseState::new(*self.state.rule());
+        for coords in self.game_state.selection.iter() {
+            let cell = self.state.get_cell(coords.x, coords.y);
+            if cell != Cell::Dead {
+                sparse.set_cell(coords.x, coords.y, cell);
+            }
+        }
+        if sparse.population() == 0 {
+            return "no live cells selected".to_string();
+        }
+
+        match CycleDetector::default().run(&mut sparse, SHIP_GENERATIONS) {
+            Some(cycle) => match cycle.speed() {
+                Some(speed) => format!(
+                    "spaceship {speed}, period {}, moving by ({},{})",
+                    cycle.period, cycle.dx, cycle.dy
+                ),
+                None => format!("not a spaceship, {cycle}"),
+            },
+            None => format!("no cycle within {SHIP_GENERATIONS} generations"),
+        }
+    }
 }
@@ -0,0 +1,286 @@
+//! History of the board, allowing edits to be undone and generations to be
+//! rewound.
+//!
+//! Two histories are kept:
+//!
+//! - Edits: the board is recorded before each edit, eg. toggling cells or
+//!   clearing the board. Undoing an edit restores the board as it was, and the
+//!   undone board can be restored back with redo until the next edit.
+//!
+//! - Generations: the board is recorded before the first simulation step of
+//!   each frame, so that the simulation can be stepped back through the boards
+//!   displayed. Boards too large to fit the memory limit are not recorded, nor
+//!   even taken.
+//!
+//! Boards are recorded as their non-dead cells. Memory use is capped: once the
+//! limit is reached, the oldest generations are dropped first, then the
+//! oldest edits, and the oldest undone edits last.
+
+use std::{collections::VecDeque, mem};
+
+use crate::core::{Cell, Coords, Simulation};
+
+/// Non-dead cells of a board at some generation.
+pub struct Snapshot {
+    generation: u64,
+    cells: Vec<(Coords, Cell)>,
+}
+
+impl Snapshot {
+    pub fn take<S: Simulation + ?Sized>(sim: &S) -> Self {
+        Snapshot {
+            generation: sim.generation(),
+            cells: sim.live_cells().collect(),
+        }
+    }
+
+    /// Replaces the cells and the generation of the simulation.
+    pub fn restore<S: Simulation + ?Sized>(&self, sim: &mut S) {
+        sim.clear();
+        for &(coords, cell) in &self.cells {
+            sim.set_cell(coords.x, coords.y, cell);
+        }
+        sim.set_generation(self.generation);
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Returns an estimate of the memory used, in bytes.
+    fn size(&self) -> usize {
+        Self::estimate(self.cells.len())
+    }
+
+    /// Returns an estimate of the memory used by a snapshot of the given number
+    /// of cells, in bytes.
+    fn estimate(cells: usize) -> usize {
+        mem::size_of::<Self>() + cells * mem::size_of::<(Coords, Cell)>()
+    }
+}
+
+pub struct History {
+    // Memory cap in bytes.
+    limit: usize,
+    // Memory used by the snapshots in bytes.
+    size: usize,
+    // Boards before each step, oldest first.
+    generations: VecDeque<Snapshot>,
+    // Boards before each edit, oldest first.
+    undo: VecDeque<Snapshot>,
+    // Boards before each undo, latest undo last.
+    redo: Vec<Snapshot>,
+}
+
+impl History {
+    pub fn new(limit: usize) -> Self {
+        History {
+            limit,
+            size: 0,
+            generations: VecDeque::new(),
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+        }
+    }
+
+    /// Returns whether a board of the given population can be recorded within
+    /// the memory limit, dying cells aside.
+    pub fn fits(&self, population: usize) -> bool {
+        Snapshot::estimate(population) <= self.limit
+    }
+
+    /// Records the board before a simulation step.
+    pub fn record_generation(&mut self, snapshot: Snapshot) {
+        self.size += snapshot.size();
+        self.generations.push_back(snapshot);
+        self.trim();
+    }
+
+    /// Records the board before an edit, which makes redo impossible.
+    pub fn record_edit(&mut self, snapshot: Snapshot) {
+        for snapshot in self.redo.drain(..) {
+            self.size -= snapshot.size();
+        }
+        self.size += snapshot.size();
+        self.undo.push_back(snapshot);
+        self.trim();
+    }
+
+    /// Returns the board `n` steps back, or the oldest one recorded when fewer
+    /// steps are available. The boards of the later steps are dropped.
+    pub fn rewind(&mut self, n: usize) -> Option<Snapshot> {
+        let mut snapshot = None;
+        for _ in 0..n {
+            let Some(previous) = self.generations.pop_back() else {
+                break;
+            };
+            self.size -= previous.size();
+            snapshot = Some(previous);
+        }
+        snapshot
+    }
+
+    /// Returns the board before the latest edit, recording the current one for
+    /// redo.
+    pub fn undo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let snapshot = self.undo.pop_back()?;
+        self.size += current.size();
+        self.size -= snapshot.size();
+        self.redo.push(current);
+        self.forget_after(snapshot.generation);
+        self.trim();
+        Some(snapshot)
+    }
+
+    /// Returns the board before the latest undo, recording the current one for
+    /// undo.
+    pub fn redo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let snapshot = self.redo.pop()?;
+        self.size += current.size();
+        self.size -= snapshot.size();
+        self.undo.push_back(current);
+        self.forget_after(snapshot.generation);
+        self.trim();
+        Some(snapshot)
+    }
+
+    /// Returns the number of steps that can be rewound.
+    pub fn steps(&self) -> usize {
+        self.generations.len()
+    }
+
+    /// Drops the generations recorded from the given one onwards, which no
+    /// longer precede the board once it is restored.
+    fn forget_after(&mut self, generation: u64) {
+        while self
+            .generations
+            .back()
+            .is_some_and(|last| last.generation >= generation)
+        {
+            let snapshot = self
+                .generations
+                .pop_back()
+                .expect("generations is not empty");
+            self.size -= snapshot.size();
+        }
+    }
+
+    /// Drops the oldest generations, then the oldest edits, then the oldest
+    /// redos, until memory use fits the limit.
+    fn trim(&mut self) {
+        while self.size > self.limit {
+            let dropped = match self.generations.pop_front() {
+                Some(snapshot) => snapshot,
+                None => match self.undo.pop_front() {
+                    Some(snapshot) => snapshot,
+                    None if !self.redo.is_empty() => self.redo.remove(0),
+                    None => break,
+                },
+            };
+            self.size -= dropped.size();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_support::sparse;
+
+    fn population(snapshot: &Snapshot) -> usize {
+        snapshot.cells.len()
+    }
+
+    #[test]
+    fn rewinds_generations() {
+        let mut history = History::new(usize::MAX);
+        // blinker
+        let mut sim = sparse(&[(0, 0), (1, 0), (2, 0)]);
+        for _ in 0..5 {
+            history.record_generation(Snapshot::take(&sim));
+            sim.step();
+        }
+
+        history.rewind(2).unwrap().restore(&mut sim);
+        assert_eq!(sim.generation(), 3);
+        assert_eq!(sim.get_cell(1, -1), Cell::Alive);
+        assert_eq!(history.steps(), 3);
+
+        history.rewind(10).unwrap().restore(&mut sim);
+        assert_eq!(sim.generation(), 0);
+        assert_eq!(sim.get_cell(0, 0), Cell::Alive);
+        assert!(history.rewind(1).is_none());
+    }
+
+    #[test]
+    fn undoes_and_redoes_edits() {
+        let mut history = History::new(usize::MAX);
+        let mut sim = sparse(&[]);
+        for x in 0..3 {
+            history.record_edit(Snapshot::take(&sim));
+            sim.set_cell(x, 0, Cell::Alive);
+        }
+
+        history
+            .undo(Snapshot::take(&sim))
+            .unwrap()
+            .restore(&mut sim);
+        history
+            .undo(Snapshot::take(&sim))
+            .unwrap()
+            .restore(&mut sim);
+        assert_eq!(sim.population(), 1);
+        history
+            .redo(Snapshot::take(&sim))
+            .unwrap()
+            .restore(&mut sim);
+        assert_eq!(sim.population(), 2);
+
+        // a new edit discards the undone ones
+        history.record_edit(Snapshot::take(&sim));
+        assert!(history.redo(Snapshot::take(&sim)).is_none());
+        assert_eq!(population(&history.undo(Snapshot::take(&sim)).unwrap()), 2);
+    }
+
+    #[test]
+    fn undo_forgets_later_generations() {
+        let mut history = History::new(usize::MAX);
+        let mut sim = sparse(&[(0, 0), (1, 0), (2, 0)]);
+        history.record_generation(Snapshot::take(&sim));
+        sim.step();
+        history.record_edit(Snapshot::take(&sim));
+        sim.set_cell(10, 10, Cell::Alive);
+        for _ in 0..3 {
+            history.record_generation(Snapshot::take(&sim));
+            sim.step();
+        }
+
+        history
+            .undo(Snapshot::take(&sim))
+            .unwrap()
+            .restore(&mut sim);
+        assert_eq!(sim.generation(), 1);
+        assert_eq!(history.steps(), 1);
+    }
+
+    #[test]
+    fn caps_memory_use() {
+        let sim = sparse(&[(0, 0), (1, 0), (2, 0)]);
+        let size = Snapshot::take(&sim).size();
+        let mut history = History::new(3 * size);
+        history.record_edit(Snapshot::take(&sim));
+        for _ in 0..5 {
+            history.record_generation(Snapshot::take(&sim));
+        }
+        assert_eq!(history.steps(), 2);
+        assert!(history.size <= 3 * size);
+        assert!(history.fits(sim.population()) && !history.fits(history.limit));
+
+        // edits go once no generation is left
+        for _ in 0..4 {
+            history.record_edit(Snapshot::take(&sim));
+        }
+        assert_eq!(history.steps(), 0);
+        assert_eq!(history.undo.len(), 3);
+    }
+}
@@ -2,28 +2,51 @@ use sdl2::rect::Rect;
 
 use crate::core::Config;
 
+// Largest width of the population sparkline, one pixel per generation.
+const SPARKLINE_WIDTH: u32 = 128;
+// Largest size of the board view in pixels, larger boards being panned and
+// zoomed through it.
+const BOARD_MAX_WIDTH: u32 = 1280;
+const BOARD_MAX_HEIGHT: u32 = 800;
+
 pub struct Layout {
     pub(crate) statusbar: Rect,
     pub(crate) cmdline: Rect,
-    board: Rect,
+    pub(crate) sparkline: Rect,
+    pub(crate) library: Rect,
+    pub(crate) board: Rect,
 
     window_width: u32,
 
+    pub(crate) cols: u32,
+    pub(crate) rows: u32,
     pub(crate) scale: u32,
 }
 
 impl Layout {
     #[rustfmt::skip]
     pub fn new(config: &Config, scale: u32) -> Self {
-        let window_width = config.cols * scale;
-        let board_height = config.rows * scale;
+        let (cols, rows) = (config.cols, config.rows);
+        let window_width = config.cols.saturating_mul(scale).min(BOARD_MAX_WIDTH);
+        let board_height = config.rows.saturating_mul(scale).min(BOARD_MAX_HEIGHT);
         let bar_height = 20;
 
         let board = Rect::new(0, 0, window_width, board_height);
         let statusbar = Rect::new(0, board.bottom(), window_width, bar_height);
         let cmdline = Rect::new(0, statusbar.bottom(), window_width, bar_height);
-
-        Layout { board, statusbar, cmdline, window_width, scale }
+        // right end of the statusbar
+        let sparkline_width = (window_width / 4).min(SPARKLINE_WIDTH);
+        let sparkline = Rect::new(
+            statusbar.right() - sparkline_width as i32 - 4, statusbar.y() + 3,
+            sparkline_width, bar_height - 6,
+        );
+        // over the board
+        let library = Rect::new(
+            board.x() + 8, board.y() + 8,
+            board.width().saturating_sub(16), board.height().saturating_sub(16),
+        );
+
+        Layout { board, statusbar, cmdline, sparkline, library, window_width, cols, rows, scale }
     }
 
     pub fn window_width(&self) -> u32 {
@@ -0,0 +1,192 @@
+//! Registers hold copies of selected cells, to be pasted elsewhere.
+//!
+//! Like in Vi, registers are named by a lowercase letter, and the unnamed
+//! register `"` is used when no name is given. The content of a register is a
+//! `Pattern` covering the bounds of the selection it was copied from, so that
+//! it can be exchanged with the clipboard as RLE text.
+//!
+//! Pasting combines the register with the board cells within its bounds,
+//! according to the paste mode.
+
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use crate::core::{Bounds, Cell, Coords, Simulation, format::Pattern};
+
+/// Register used when no name is given.
+pub const UNNAMED: char = '"';
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum PasteMode {
+    // Cells of the register are added to the board.
+    #[default]
+    Or,
+    // Only the board cells also alive in the register are kept.
+    And,
+    // Cells of the register toggle the board cells.
+    Xor,
+    // The board is replaced by the register within its bounds.
+    Overwrite,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ParsePasteModeError;
+
+#[derive(Default)]
+pub struct Registers {
+    registers: HashMap<char, Pattern>,
+}
+
+impl Registers {
+    /// Returns whether a register name is valid.
+    pub fn is_valid_name(name: char) -> bool {
+        name == UNNAMED || name.is_ascii_lowercase()
+    }
+
+    pub fn get(&self, name: char) -> Option<&Pattern> {
+        self.registers.get(&name)
+    }
+
+    pub fn set(&mut self, name: char, pattern: Pattern) {
+        self.registers.insert(name, pattern);
+    }
+}
+
+/// Copies the selected cells of a simulation, relative to the top-left corner
+/// of the selection. Dead cells are part of the copy, as its bounds.
+pub fn copy<'a, S, I>(sim: &S, selection: I) -> Pattern
+where
+    S: Simulation + ?Sized,
+    I: IntoIterator<Item = &'a Coords>,
+{
+    let selection: Vec<_> = selection.into_iter().copied().collect();
+    let Some(bounds) = Bounds::from_coords(selection.iter().copied()) else {
+        return Pattern::default();
+    };
+    let cells = selection
+        .iter()
+        .map(|&Coords { x, y }| (x, y, sim.get_cell(x, y)))
+        .filter(|&(_, _, cell)| cell != Cell::Dead)
+        .map(|(x, y, cell)| {
+            let coords = Coords {
+                x: x - bounds.x_min,
+                y: y - bounds.y_min,
+            };
+            (coords, cell)
+        })
+        .collect();
+
+    Pattern {
+        rule: Some(*sim.rule()),
+        width: bounds.width(),
+        height: bounds.height(),
+        cells,
+        ..Pattern::default()
+    }
+}
+
+impl PasteMode {
+    /// Pastes a pattern with its top-left corner at (x,y).
+    pub fn paste<S: Simulation + ?Sized>(self, pattern: &Pattern, sim: &mut S, x: i32, y: i32) {
+        let pasted: HashMap<Coords, Cell> = pattern.cells.iter().copied().collect();
+
+        for py in 0..pattern.height as i32 {
+            for px in 0..pattern.width as i32 {
+                let pasted = pasted.get(&Coords { x: px, y: py }).copied();
+                let board = sim.get_cell(x + px, y + py);
+                let cell = match (self, pasted) {
+                    (PasteMode::Or, Some(cell)) => cell,
+                    (PasteMode::And, None) => Cell::Dead,
+                    (PasteMode::Xor, Some(_)) => {
+                        let mut cell = board;
+                        cell.toggle();
+                        cell
+                    }
+                    (PasteMode::Overwrite, pasted) => pasted.unwrap_or(Cell::Dead),
+                    _ => board,
+                };
+                if cell != board {
+                    sim.set_cell(x + px, y + py, cell);
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for PasteMode {
+    type Err = ParsePasteModeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "or" => Ok(PasteMode::Or),
+            "and" => Ok(PasteMode::And),
+            "xor" => Ok(PasteMode::Xor),
+            "overwrite" | "copy" => Ok(PasteMode::Overwrite),
+            _ => Err(ParsePasteModeError),
+        }
+    }
+}
+
+impl fmt::Display for PasteMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PasteMode::Or => "or",
+            PasteMode::And => "and",
+            PasteMode::Xor => "xor",
+            PasteMode::Overwrite => "overwrite",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl fmt::Display for ParsePasteModeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "paste mode must be one of or, and, xor or overwrite")
+    }
+}
+
+impl std::error::Error for ParsePasteModeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_support::{live_coords, sparse};
+
+    #[test]
+    fn copies_selected_cells() {
+        let sim = sparse(&[(5, 5), (6, 6), (9, 9)]);
+        let selection = [(4, 4), (5, 5), (6, 6), (6, 7)].map(|(x, y)| Coords { x, y });
+        let pattern = copy(&sim, &selection);
+        assert_eq!((pattern.width, pattern.height), (3, 4));
+        let mut cells: Vec<_> = pattern.cells.iter().map(|(c, _)| (c.x, c.y)).collect();
+        cells.sort();
+        assert_eq!(cells, vec![(1, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn pastes_in_every_mode() {
+        // register: alive on the diagonal of a 2x2 square
+        let pattern = copy(
+            &sparse(&[(0, 0), (1, 1)]),
+            &[(0, 0), (1, 0), (0, 1), (1, 1)].map(|(x, y)| Coords { x, y }),
+        );
+        // board: alive on the top row
+        let paste = |mode: PasteMode| {
+            let mut sim = sparse(&[(0, 0), (1, 0)]);
+            mode.paste(&pattern, &mut sim, 0, 0);
+            live_coords(&sim)
+        };
+        assert_eq!(paste(PasteMode::Or), vec![(0, 0), (1, 0), (1, 1)]);
+        assert_eq!(paste(PasteMode::And), vec![(0, 0)]);
+        assert_eq!(paste(PasteMode::Xor), vec![(1, 0), (1, 1)]);
+        assert_eq!(paste(PasteMode::Overwrite), vec![(0, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn parses_paste_modes() {
+        assert_eq!("XOR".parse(), Ok(PasteMode::Xor));
+        assert_eq!("copy".parse(), Ok(PasteMode::Overwrite));
+        assert_eq!("nand".parse::<PasteMode>(), Err(ParsePasteModeError));
+        assert!(Registers::is_valid_name('a'));
+        assert!(!Registers::is_valid_name('A'));
+    }
+}
@@ -1,10 +1,13 @@
 use super::layout::Layout;
 use crate::{
-    core::State,
+    core::Simulation,
     render::sdl::{
         game_state::GameState,
         theme::Theme,
-        widget::{Widget, board::Board, cmdline::Cmdline, statusbar::Statusbar},
+        widget::{
+            Widget, board::Board, cmdline::Cmdline, library::Library, sparkline::Sparkline,
+            statusbar::Statusbar,
+        },
     },
 };
 use sdl2::{
@@ -19,7 +22,7 @@ pub struct RenderingContext<'a, 'b> {
     pub(crate) layout: &'a Layout,
     pub(crate) texture_creator: &'a TextureCreator<WindowContext>,
     pub(crate) theme: &'a Theme,
-    pub(crate) state: &'b State,
+    pub(crate) state: &'b dyn Simulation,
     pub(crate) game_state: &'b GameState,
 }
 
@@ -38,7 +41,9 @@ impl<'a> Renderer<'a> {
         let theme = Theme::default();
         let widgets: Vec<Box<dyn Widget>> = vec![
             Box::new(Board {}),
+            Box::new(Library {}),
             Box::new(Statusbar {}),
+            Box::new(Sparkline {}),
             Box::new(Cmdline {}),
         ];
 
@@ -52,7 +57,7 @@ impl<'a> Renderer<'a> {
         }
     }
 
-    pub fn draw(&mut self, state: &State, game_state: &GameState) -> Result<(), String> {
+    pub fn draw(&mut self, state: &dyn Simulation, game_state: &GameState) -> Result<(), String> {
         let mut rendering_ctx = RenderingContext {
             canvas: &mut self.canvas,
             font: &self.font,
@@ -18,12 +18,30 @@
 //!
 //! - When pressing `x` key, the existing selection is cleared.
 //!
+//! - When pressing `f,F` keys, the selected cells are mirrored horizontally or
+//!   vertically within the selection bounds, and `\` transposes them around
+//!   the top-left corner. Unlike rotations, these move the cells along with the
+//!   selection.
+//!
 //! - When pressing `Space` key, the selected cells are toggled, meaning that
 //! alive cells become dead and viceversa.
+//!
+//! - When dragging with Right mouse button, a rectangle or a line is selected,
+//!   depending on the selection tool, which is switched with the `v` key.
+//!
+//! - When double-clicking a live cell with Right mouse button, the object it
+//!   belongs to is selected, and `Ctrl+a` selects every live cell.
+//!
+//! Dragged and double-clicked selections replace the existing selection, or
+//! are added to, subtracted from or intersected with it while holding `Shift`,
+//! `Ctrl` or `Alt` respectively. `Ctrl+a` always replaces it.
 
-use std::{collections::HashSet, iter::Iterator};
+use std::{
+    collections::{HashSet, VecDeque},
+    iter::Iterator,
+};
 
-use crate::core::Coords;
+use crate::core::{Cell, Coords, Simulation};
 
 struct Bounds {
     x_min: i32,
@@ -51,12 +69,135 @@ fn calc_bounds(selection: &HashSet<Coords>) -> Option<Bounds> {
         })
 }
 
+/// How new coords are combined with the existing selection.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SelectMode {
+    #[default]
+    Replace,
+    Add,
+    Subtract,
+    Intersect,
+}
+
+/// Shape selected when dragging.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SelectTool {
+    #[default]
+    Rect,
+    Line,
+}
+
+impl SelectTool {
+    /// Returns the coords selected by dragging from `from` to `to`.
+    pub fn shape(self, from: Coords, to: Coords) -> Vec<Coords> {
+        match self {
+            SelectTool::Rect => rect(from, to),
+            SelectTool::Line => line(from, to),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SelectTool::Rect => "rectangle",
+            SelectTool::Line => "line",
+        }
+    }
+}
+
+/// Coords of the filled rectangle having `from` and `to` as opposite corners.
+pub fn rect(from: Coords, to: Coords) -> Vec<Coords> {
+    let (x_min, x_max) = (from.x.min(to.x), from.x.max(to.x));
+    let (y_min, y_max) = (from.y.min(to.y), from.y.max(to.y));
+    (y_min..=y_max)
+        .flat_map(|y| (x_min..=x_max).map(move |x| Coords { x, y }))
+        .collect()
+}
+
+/// Coords of the line from `from` to `to`, using Bresenham's algorithm.
+pub fn line(from: Coords, to: Coords) -> Vec<Coords> {
+    let (dx, dy) = ((to.x - from.x).abs(), -(to.y - from.y).abs());
+    let (sx, sy) = ((to.x - from.x).signum(), (to.y - from.y).signum());
+    let mut err = dx + dy;
+    let mut coords = from;
+    let mut line = vec![coords];
+    while coords != to {
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            coords.x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            coords.y += sy;
+        }
+        line.push(coords);
+    }
+    line
+}
+
+/// Coords of the object containing `start`: its non-dead cells connected to
+/// each other through any of their eight neighbors. Objects are followed
+/// across the edges of wrapping boards.
+pub fn connected<S: Simulation + ?Sized>(sim: &S, start: Coords) -> Vec<Coords> {
+    let is_live = |coords: &Coords| sim.get_cell(coords.x, coords.y) != Cell::Dead;
+    let Some(start) = sim.wrap_coords(&start).filter(is_live) else {
+        return Vec::new();
+    };
+
+    let mut visited = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(Coords { x, y }) = queue.pop_front() {
+        for (dx, dy) in NEIGHBORS {
+            let neighbor = Coords {
+                x: x + dx,
+                y: y + dy,
+            };
+            let Some(neighbor) = sim.wrap_coords(&neighbor) else {
+                continue;
+            };
+            if is_live(&neighbor) && visited.insert(neighbor) {
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    visited.into_iter().collect()
+}
+
+/// Coords of every non-dead cell.
+pub fn live<S: Simulation + ?Sized>(sim: &S) -> Vec<Coords> {
+    sim.live_cells().map(|(coords, _)| coords).collect()
+}
+
+#[rustfmt::skip]
+const NEIGHBORS: [(i32, i32); 8] = [
+    (-1, -1), (0, -1), (1, -1),
+    (-1,  0),          (1,  0),
+    (-1,  1), (0,  1), (1,  1),
+];
+
 #[derive(Default, Debug)]
 pub struct Selection {
     coords: HashSet<Coords>,
 }
 
 impl Selection {
+    /// Combines coords with the existing selection.
+    pub fn apply<I: IntoIterator<Item = Coords>>(&mut self, coords: I, mode: SelectMode) {
+        match mode {
+            SelectMode::Replace => self.coords = coords.into_iter().collect(),
+            SelectMode::Add => self.coords.extend(coords),
+            SelectMode::Subtract => {
+                for coords in coords {
+                    self.coords.remove(&coords);
+                }
+            }
+            SelectMode::Intersect => {
+                let coords: HashSet<_> = coords.into_iter().collect();
+                self.coords.retain(|c| coords.contains(c));
+            }
+        }
+    }
+
     /// Clear the existing selection.
     pub fn clear(&mut self) {
         self.coords.clear();
@@ -135,6 +276,11 @@ impl Selection {
         }
     }
 
+    /// Returns the center of the selection, `None` when it is empty.
+    pub fn center(&self) -> Option<Coords> {
+        self.calc_center()
+    }
+
     /// Rotate existing selection around its center by 90 degrees, either
     /// clockwise or counterclockwise.
     ///
@@ -175,6 +321,64 @@ impl Selection {
         }
     }
 
+    /// Moves the selected cells, and the selection with them, to the image of
+    /// their coords. Cells are read before any of them is written, so that
+    /// images may overlap the selection.
+    fn transform<S, F>(&mut self, sim: &mut S, image: F)
+    where
+        S: Simulation + ?Sized,
+        F: Fn(Coords, &Bounds) -> Coords,
+    {
+        let Some(bounds) = calc_bounds(&self.coords) else {
+            return;
+        };
+        let cells: Vec<_> = self
+            .coords
+            .iter()
+            .map(|&coords| (coords, sim.get_cell(coords.x, coords.y)))
+            .collect();
+        for (coords, cell) in &cells {
+            if *cell != Cell::Dead {
+                sim.set_cell(coords.x, coords.y, Cell::Dead);
+            }
+        }
+        self.coords = cells
+            .into_iter()
+            .map(|(coords, cell)| {
+                let image = image(coords, &bounds);
+                if cell != Cell::Dead {
+                    sim.set_cell(image.x, image.y, cell);
+                }
+                image
+            })
+            .collect();
+    }
+
+    /// Mirror the selected cells left to right, within the selection bounds.
+    pub fn flip_x<S: Simulation + ?Sized>(&mut self, sim: &mut S) {
+        self.transform(sim, |Coords { x, y }, bounds| Coords {
+            x: bounds.x_min + bounds.x_max - x,
+            y,
+        });
+    }
+
+    /// Mirror the selected cells top to bottom, within the selection bounds.
+    pub fn flip_y<S: Simulation + ?Sized>(&mut self, sim: &mut S) {
+        self.transform(sim, |Coords { x, y }, bounds| Coords {
+            x,
+            y: bounds.y_min + bounds.y_max - y,
+        });
+    }
+
+    /// Mirror the selected cells across the diagonal going down-right from the
+    /// top-left corner of the selection bounds, swapping rows and columns.
+    pub fn transpose<S: Simulation + ?Sized>(&mut self, sim: &mut S) {
+        self.transform(sim, |Coords { x, y }, bounds| Coords {
+            x: bounds.x_min + y - bounds.y_min,
+            y: bounds.y_min + x - bounds.x_min,
+        });
+    }
+
     /// Rotate existing selection around its center by 90 degrees counterclockwise.
     pub fn rotate_left(&mut self) {
         self.rotate(false);
@@ -185,6 +389,15 @@ impl Selection {
         self.rotate(true);
     }
 
+    /// Returns the top-left corner of the selection bounds, which is where
+    /// patterns are inserted.
+    pub fn top_left(&self) -> Option<Coords> {
+        calc_bounds(&self.coords).map(|bounds| Coords {
+            x: bounds.x_min,
+            y: bounds.y_min,
+        })
+    }
+
     /// Adds/removes (x,y) to/from existing selection according to whether it is
     /// already included or not.
     pub fn toggle<C: Into<Coords>>(&mut self, coords: C) {
@@ -286,4 +499,82 @@ mod tests {
         assert!(selection.contains((2, 2)));
         assert!(selection.contains((3, 3)));
     }
+
+    #[test]
+    fn selects_rects_and_lines() {
+        let coords = |c: &[(i32, i32)]| c.iter().map(|&c| Coords::from(c)).collect::<Vec<_>>();
+        assert_eq!(
+            rect((2, 1).into(), (1, 2).into()),
+            coords(&[(1, 1), (2, 1), (1, 2), (2, 2)])
+        );
+        assert_eq!(
+            line((0, 0).into(), (4, 2).into()),
+            coords(&[(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)])
+        );
+        assert_eq!(line((3, 3).into(), (3, 3).into()), coords(&[(3, 3)]));
+        assert_eq!(line((0, 2).into(), (0, 0).into()).len(), 3);
+    }
+
+    #[test]
+    fn combines_selections() {
+        let mut selection = Selection::default();
+        selection.apply(rect((0, 0).into(), (2, 0).into()), SelectMode::Replace);
+        selection.apply(rect((2, 0).into(), (3, 0).into()), SelectMode::Add);
+        assert_eq!(selection.len(), 4);
+        selection.apply([Coords::from((0, 0))], SelectMode::Subtract);
+        assert_eq!(selection.len(), 3);
+        selection.apply(rect((0, 0).into(), (2, 0).into()), SelectMode::Intersect);
+        assert_eq!(selection.len(), 2);
+        assert!(selection.contains((1, 0)) && selection.contains((2, 0)));
+        selection.apply([Coords::from((9, 9))], SelectMode::Replace);
+        assert_eq!(selection.len(), 1);
+    }
+
+    #[test]
+    fn selects_connected_objects() {
+        use crate::core::{Config, State};
+
+        let mut state = State::new(&Config {
+            cols: 20,
+            rows: 20,
+            ..Config::default()
+        });
+        // a blinker across the left edge, and a lone cell
+        for (x, y) in [(19, 5), (0, 5), (1, 5), (10, 10)] {
+            state.set_cell(x, y, Cell::Alive);
+        }
+        assert_eq!(connected(&state, (0, 5).into()).len(), 3);
+        assert_eq!(connected(&state, (10, 10).into()).len(), 1);
+        assert!(connected(&state, (5, 5).into()).is_empty());
+        assert_eq!(live(&state).len(), 4);
+    }
+
+    #[test]
+    fn mirrors_cells_with_selection() {
+        use crate::core::test_support::{live_coords, sparse};
+
+        // glider heading down-right, in a 3x3 selection at (10,10)
+        let mut sim = sparse(&[(11, 10), (12, 11), (10, 12), (11, 12), (12, 12)]);
+        let mut selection = Selection::default();
+        selection.apply(rect((10, 10).into(), (12, 12).into()), SelectMode::Replace);
+
+        selection.flip_x(&mut sim);
+        assert_eq!(
+            live_coords(&sim),
+            vec![(10, 11), (10, 12), (11, 10), (11, 12), (12, 12)]
+        );
+        selection.flip_y(&mut sim);
+        assert_eq!(
+            live_coords(&sim),
+            vec![(10, 10), (10, 11), (11, 10), (11, 12), (12, 10)]
+        );
+
+        // a non-square selection changes shape
+        let mut sim = sparse(&[(3, 0)]);
+        let mut selection = Selection::default();
+        selection.apply(rect((0, 0).into(), (3, 0).into()), SelectMode::Replace);
+        selection.transpose(&mut sim);
+        assert_eq!(live_coords(&sim), vec![(0, 3)]);
+        assert!(selection.contains((0, 3)) && !selection.contains((3, 0)));
+    }
 }
@@ -16,12 +16,17 @@ pub struct Palette {
     pub(crate) bg: Color,
     pub(crate) cell_alive: Color,
     pub(crate) cell_dead: Color,
+    pub(crate) cell_dying: Color,
     pub(crate) cell_selected: Color,
+    pub(crate) cell_pasted: Color,
     // pub(crate) help_bg: Color,
     pub(crate) cmdline_bg: Color,
     pub(crate) cmdline_text: Color,
     pub(crate) status_bg: Color,
     pub(crate) status_text: Color,
+    pub(crate) sparkline: Color,
+    pub(crate) library_bg: Color,
+    pub(crate) library_text: Color,
 }
 
 impl Default for Palette {
@@ -30,12 +35,17 @@ impl Default for Palette {
             bg: Color::RGBA(0x00, 0x00, 0x00, 0xff),
             cell_alive: Color::RGBA(0xff, 0xff, 0xff, 0xff),
             cell_dead: Color::RGBA(0x22, 0x22, 0x32, 0xff),
+            cell_dying: Color::RGBA(0xbf, 0x78, 0x9f, 0xff),
             cell_selected: Color::RGBA(0xff, 0x00, 0xff, 0xff),
+            cell_pasted: Color::RGBA(0x78, 0xbf, 0x9f, 0xff),
             // help_bg: Color::RGBA(0x00, 0x00, 0x00, 0x9a),
             cmdline_bg: Color::RGBA(0x00, 0x00, 0x00, 0xff),
             cmdline_text: Color::RGBA(0xff, 0xff, 0xff, 0xff),
             status_bg: Color::RGBA(0x78, 0x9f, 0xbf, 0xff),
             status_text: Color::RGBA(0x00, 0x00, 0x00, 0xff),
+            sparkline: Color::RGBA(0x22, 0x22, 0x32, 0xff),
+            library_bg: Color::RGBA(0x00, 0x00, 0x00, 0xff),
+            library_text: Color::RGBA(0xff, 0xff, 0xff, 0xff),
         }
     }
 }
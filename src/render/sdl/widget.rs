@@ -6,6 +6,8 @@ pub trait Widget {
 
 pub mod board;
 pub mod cmdline;
+pub mod library;
 pub mod pane;
+pub mod sparkline;
 pub mod statusbar;
 pub mod text;
@@ -1,46 +1,223 @@
-use sdl2::rect::Rect;
+use std::ops::RangeInclusive;
 
-use crate::core::Cell;
+use sdl2::pixels::Color;
+use sdl2::rect::{Point, Rect};
+
+use crate::core::{Bounds, Cell, Coords};
+use crate::render::sdl::camera::Camera;
 use crate::render::sdl::renderer::RenderingContext;
 use crate::render::sdl::widget::Widget;
 
+// Smallest cells, in pixels, drawn with a grid.
+const GRID_MIN_PIXELS: u32 = 4;
+
+/// Draws the board through the camera.
+///
+/// Both zoom levels draw each cell once, where it lies on the board: boards
+/// that wrap around are not repeated beyond their edges, and the view around
+/// bounded or wrapping boards is left to the background.
 pub struct Board;
 
-impl Widget for Board {
-    fn render(&self, ctx: &mut RenderingContext) -> Result<(), String> {
-        let scale = 10;
-        let grid = if ctx.game_state.show_grid { 1 } else { 0 };
-
-        for (coords, cell) in ctx.state.iter() {
-            let rect = Rect::new(
-                coords.x * scale as i32,
-                coords.y * scale as i32,
-                scale - grid,
-                scale - grid,
-            );
-
-            match cell {
+impl Board {
+    /// Dying cells fade from `cell_dying` towards `cell_dead` as they get
+    /// closer to death.
+    fn dying_color(ctx: &RenderingContext, n: u8, states: u8) -> Color {
+        let from = ctx.theme.palette.cell_dying;
+        let to = ctx.theme.palette.cell_dead;
+        let t = n.saturating_sub(1) as f32 / states.saturating_sub(2).max(1) as f32;
+        Self::lerp(from, to, t)
+    }
+
+    /// Pixels covering several cells go from `cell_dead` towards `cell_alive`
+    /// as more of their cells are non-dead.
+    fn density_color(ctx: &RenderingContext, alive: u32, cells: u32) -> Color {
+        let from = ctx.theme.palette.cell_dead;
+        let to = ctx.theme.palette.cell_alive;
+        Self::lerp(from, to, alive as f32 / cells as f32)
+    }
+
+    fn lerp(from: Color, to: Color, t: f32) -> Color {
+        let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t) as u8;
+        Color::RGB(lerp(from.r, to.r), lerp(from.g, to.g), lerp(from.b, to.b))
+    }
+
+    /// Square covered by a cell, shrunk by `shrink` pixels and moved by
+    /// `offset` pixels. Cells smaller than a pixel cover a single pixel.
+    fn cell_rect(camera: &Camera, coords: Coords, offset: i32, shrink: u32) -> Rect {
+        let pixel = camera.cell_to_screen(coords);
+        let side = camera.pixels_per_cell().saturating_sub(shrink).max(1);
+        Rect::new(pixel.x + offset, pixel.y + offset, side, side)
+    }
+
+    /// Returns the pixels left between cells for the grid, none when cells are
+    /// too small to tell them apart.
+    fn grid(ctx: &RenderingContext) -> u32 {
+        let pixels = ctx.game_state.camera.pixels_per_cell();
+        if ctx.game_state.show_grid && pixels >= GRID_MIN_PIXELS {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// Returns the bounds of the visible cells lying on the board, `None` when
+    /// the board is out of view.
+    ///
+    /// Boards are rectangles with a corner at (0,0), so each axis is checked
+    /// along the origin of the other one. Unbounded engines keep every cell on
+    /// the board.
+    fn on_board(ctx: &RenderingContext) -> Option<Bounds> {
+        let board = ctx.layout.board;
+        let visible = ctx.game_state.camera.visible(board.width(), board.height());
+        let on_board = |x, y| {
+            let coords = Coords { x, y };
+            ctx.state.wrap_coords(&coords) == Some(coords)
+        };
+        let span = |cells: RangeInclusive<i32>, on_board: &dyn Fn(i32) -> bool| {
+            cells.filter(|&c| on_board(c)).fold(None, |span, c| {
+                Some(span.map_or((c, c), |(first, _)| (first, c)))
+            })
+        };
+        let (x_min, x_max) = span(visible.x_min..=visible.x_max, &|x| on_board(x, 0))?;
+        let (y_min, y_max) = span(visible.y_min..=visible.y_max, &|y| on_board(0, y))?;
+        Some(Bounds {
+            x_min,
+            x_max,
+            y_min,
+            y_max,
+        })
+    }
+
+    /// Draws each visible cell of the board as a square of pixels.
+    fn render_cells(ctx: &mut RenderingContext) -> Result<(), String> {
+        let Some(area) = Self::on_board(ctx) else {
+            return Ok(());
+        };
+        let camera = &ctx.game_state.camera;
+        let grid = Self::grid(ctx);
+        let states = ctx.state.rule().states();
+
+        for (x, y) in
+            (area.y_min..=area.y_max).flat_map(|y| (area.x_min..=area.x_max).map(move |x| (x, y)))
+        {
+            match ctx.state.get_cell(x, y) {
                 Cell::Alive => {
                     ctx.canvas.set_draw_color(ctx.theme.palette.cell_alive);
                 }
                 Cell::Dead => {
                     ctx.canvas.set_draw_color(ctx.theme.palette.cell_dead);
                 }
+                Cell::Dying(n) => {
+                    let color = Self::dying_color(ctx, n, states);
+                    ctx.canvas.set_draw_color(color);
+                }
+            }
+            ctx.canvas
+                .fill_rect(Self::cell_rect(camera, Coords { x, y }, 0, grid))?;
+        }
+        Ok(())
+    }
+
+    /// Draws each pixel of the board shaded by the density of the non-dead
+    /// cells it covers.
+    ///
+    /// Only the live cells are visited, so that zooming out stays cheap.
+    fn render_density(ctx: &mut RenderingContext) -> Result<(), String> {
+        let Some(area) = Self::on_board(ctx) else {
+            return Ok(());
+        };
+        let camera = &ctx.game_state.camera;
+        let board = ctx.layout.board;
+        let (width, height) = (board.width() as i32, board.height() as i32);
+
+        let top_left = camera.cell_to_screen(Coords {
+            x: area.x_min,
+            y: area.y_min,
+        });
+        let bottom_right = camera.cell_to_screen(Coords {
+            x: area.x_max + 1,
+            y: area.y_max + 1,
+        });
+        let covered = Rect::from_enclose_points(
+            &[
+                Point::new(board.x() + top_left.x, board.y() + top_left.y),
+                Point::new(
+                    board.x() + bottom_right.x - 1,
+                    board.y() + bottom_right.y - 1,
+                ),
+            ],
+            board,
+        );
+        if let Some(covered) = covered {
+            ctx.canvas.set_draw_color(ctx.theme.palette.cell_dead);
+            ctx.canvas.fill_rect(covered)?;
+        }
+
+        let mut live = vec![0u32; (width * height) as usize];
+        for (coords, _) in ctx.state.live_cells() {
+            let pixel = camera.cell_to_screen(coords);
+            if (0..width).contains(&pixel.x) && (0..height).contains(&pixel.y) {
+                live[(pixel.y * width + pixel.x) as usize] += 1;
+            }
+        }
+
+        let cells = camera.cells_per_pixel().pow(2);
+        for (i, &n) in live.iter().enumerate().filter(|&(_, &n)| n > 0) {
+            ctx.canvas
+                .set_draw_color(Self::density_color(ctx, n, cells));
+            let (x, y) = (i as i32 % width, i as i32 / width);
+            ctx.canvas
+                .draw_point(Point::new(board.x() + x, board.y() + y))?;
+        }
+        Ok(())
+    }
+}
+
+impl Widget for Board {
+    fn render(&self, ctx: &mut RenderingContext) -> Result<(), String> {
+        let camera = &ctx.game_state.camera;
+        if camera.cells_per_pixel() > 1 {
+            Self::render_density(ctx)?;
+        } else {
+            Self::render_cells(ctx)?;
+        }
+        let grid = Self::grid(ctx);
+
+        // paste preview, its top-left corner at the cursor's
+        let preview = ctx.game_state.paste.and_then(|name| {
+            let pattern = ctx.game_state.registers.get(name)?;
+            Some((pattern, ctx.game_state.selection.top_left()?))
+        });
+        if let Some((pattern, origin)) = preview {
+            ctx.canvas.set_draw_color(ctx.theme.palette.cell_pasted);
+            for (coords, _) in &pattern.cells {
+                let coords = Coords {
+                    x: origin.x + coords.x,
+                    y: origin.y + coords.y,
+                };
+                let Some(coords) = ctx.state.wrap_coords(&coords) else {
+                    continue;
+                };
+                ctx.canvas
+                    .fill_rect(Self::cell_rect(camera, coords, 0, grid))?;
             }
-            ctx.canvas.fill_rect(rect)?;
         }
 
         // cursor
         ctx.canvas.set_draw_color(ctx.theme.palette.cell_selected);
-        for coords in ctx.game_state.selection.iter() {
-            let coords = ctx.state.wrap_coords(coords);
-            let rect = Rect::new(
-                (coords.x * scale as i32) + 1,
-                (coords.y * scale as i32) + 1,
-                scale - 3,
-                scale - 3,
-            );
-            ctx.canvas.draw_rect(rect)?;
+        // selected coords are drawn where they land on the board, those falling
+        // off a bounded board are not drawn
+        let drag = ctx
+            .game_state
+            .drag
+            .map(|(from, to)| ctx.game_state.select_tool.shape(from, to))
+            .unwrap_or_default();
+        for coords in ctx.game_state.selection.iter().chain(&drag) {
+            let Some(coords) = ctx.state.wrap_coords(coords) else {
+                continue;
+            };
+            ctx.canvas
+                .draw_rect(Self::cell_rect(camera, coords, 1, 3))?;
         }
 
         Ok(())
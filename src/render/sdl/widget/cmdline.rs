@@ -34,7 +34,11 @@ impl Cmdline {
 
 impl Widget for Cmdline {
     fn render(&self, ctx: &mut RenderingContext) -> Result<(), String> {
-        let text: Option<&str> = ctx.game_state.command.as_deref();
+        let text: Option<&str> = ctx
+            .game_state
+            .command
+            .as_deref()
+            .or(ctx.game_state.message.as_deref());
         let child = text.map(|t| Self::create_textbox(t, ctx.theme.palette.cmdline_text));
         let pane = Self::create_pane(ctx.layout.cmdline, ctx.theme.palette.cmdline_bg, child);
         pane.render(ctx)
@@ -0,0 +1,54 @@
+use crate::core::library;
+use crate::render::sdl::renderer::RenderingContext;
+use crate::render::sdl::widget::Widget;
+use crate::render::sdl::widget::pane::{Border, Pane};
+use crate::render::sdl::widget::text::Text;
+
+const TEXT_TITLE: &str = "Pattern library, :insert NAME to place one at the cursor";
+
+/// List of the patterns of the library, shown over the board by `:lib`.
+pub struct Library;
+
+// Lines of the list, one per pattern after the title.
+struct Entries;
+
+impl Widget for Library {
+    fn render(&self, ctx: &mut RenderingContext) -> Result<(), String> {
+        if !ctx.game_state.show_library {
+            return Ok(());
+        }
+
+        Pane {
+            rect: ctx.layout.library,
+            color: ctx.theme.palette.library_bg,
+            border: Some(Border {
+                color: ctx.theme.palette.status_bg,
+                thickness: 2,
+            }),
+            child: Some(Box::new(Entries)),
+        }
+        .render(ctx)
+    }
+}
+
+impl Widget for Entries {
+    fn render(&self, ctx: &mut RenderingContext) -> Result<(), String> {
+        let color = ctx.theme.palette.library_text;
+        let line_height = ctx.font.height();
+        let entries = library::list().map(|(name, description)| format!("{name} - {description}"));
+
+        for (i, line) in std::iter::once(TEXT_TITLE.to_string())
+            .chain(entries)
+            .enumerate()
+        {
+            Text {
+                text: &line,
+                color,
+                x: 8,
+                y: 4 + i as i32 * line_height,
+            }
+            .render(ctx)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use sdl2::rect::Point;
+
+use crate::render::sdl::renderer::RenderingContext;
+use crate::render::sdl::widget::Widget;
+
+/// Population of the latest generations, one vertical line per generation,
+/// drawn over the right end of the statusbar. Lines are scaled to the largest
+/// population shown, so the shape of the curve matters, not its height.
+pub struct Sparkline;
+
+impl Widget for Sparkline {
+    fn render(&self, ctx: &mut RenderingContext) -> Result<(), String> {
+        let Some(stats) = ctx.state.stats() else {
+            return Ok(());
+        };
+        let rect = ctx.layout.sparkline;
+        let samples: Vec<_> = stats.iter().rev().take(rect.width() as usize).collect();
+        let max = samples.iter().map(|s| s.population).max().unwrap_or(0);
+        if max == 0 {
+            return Ok(());
+        }
+
+        ctx.canvas.set_draw_color(ctx.theme.palette.sparkline);
+        let height = rect.height() as usize;
+        // latest generation on the right
+        for (i, sample) in samples.iter().enumerate() {
+            let x = rect.right() - 1 - i as i32;
+            let len = (sample.population * height).div_ceil(max) as i32;
+            if len > 0 {
+                let bottom = Point::new(x, rect.bottom() - 1);
+                ctx.canvas.draw_line(bottom, bottom.offset(0, 1 - len))?;
+            }
+        }
+        Ok(())
+    }
+}
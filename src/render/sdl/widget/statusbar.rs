@@ -1,7 +1,7 @@
 use sdl2::pixels::Color;
 use sdl2::rect::Rect;
 
-use crate::core::State;
+use crate::core::Simulation;
 use crate::render::sdl::game_state::GameState;
 use crate::render::sdl::renderer::RenderingContext;
 use crate::render::sdl::widget::Widget;
@@ -40,7 +40,7 @@ impl Statusbar {
     ///
     /// Center is computed taking `x_min, x_max, y_min, y_max` and then
     /// calculating the middle points, eg. `(x_max + x_min) / 2`.
-    fn text_coords(_game_state: &GameState, _state: &State) -> Option<String> {
+    fn text_coords(_game_state: &GameState, _state: &dyn Simulation) -> Option<String> {
         // if game_state.cursor.is_empty() {
         None
         // } else {
@@ -75,12 +75,31 @@ impl Statusbar {
         // }
     }
 
-    fn text_generation(state: &State) -> String {
-        state.generation.to_string()
+    fn text_generation(state: &dyn Simulation) -> String {
+        state.generation().to_string()
     }
 
     fn text_period(game_state: &GameState) -> String {
-        format!("{} ms", game_state.sim_period_ms)
+        match game_state.hyperspeed {
+            Some(k) => format!("{} ms x2^{}", game_state.sim_period_ms, k),
+            None => format!("{} ms", game_state.sim_period_ms),
+        }
+    }
+
+    fn text_cycle(game_state: &GameState) -> Option<String> {
+        game_state.cycle.map(|cycle| match cycle.speed() {
+            Some(speed) => format!(" P{} {} since {}", cycle.period, speed, cycle.start),
+            None => format!(" P{} since {}", cycle.period, cycle.start),
+        })
+    }
+
+    /// Display progress towards the target of `:step` or `:run until`.
+    fn text_target(game_state: &GameState, state: &dyn Simulation) -> Option<String> {
+        game_state.target.as_ref().map(|target| {
+            let done = state.generation().saturating_sub(target.start);
+            let total = target.end - target.start;
+            format!(" -> {} {}%", target.end, done.min(total) * 100 / total)
+        })
     }
 
     fn text_running(game_state: &GameState) -> &str {
@@ -98,11 +117,19 @@ impl Widget for Statusbar {
         let text_generation = Self::text_generation(ctx.state);
         let text_period = Self::text_period(ctx.game_state);
         let text_coords = Self::text_coords(ctx.game_state, ctx.state);
+        let text_cycle = Self::text_cycle(ctx.game_state);
+        let text_target = Self::text_target(ctx.game_state, ctx.state);
 
         let mut text = format!("{} {} {}", text_running, text_generation, text_period);
         if let Some(coords) = text_coords {
             text.push_str(coords.as_str());
         }
+        if let Some(target) = text_target {
+            text.push_str(target.as_str());
+        }
+        if let Some(cycle) = text_cycle {
+            text.push_str(cycle.as_str());
+        }
 
         let child = Self::create_textbox(text.as_str(), ctx.theme.palette.status_text);
         let pane = Self::create_pane(ctx.layout.statusbar, ctx.theme.palette.status_bg, child);